@@ -1,10 +1,205 @@
 app-title = Color Picker for COSMIC™ desktop
 
 ## Menu
+edit = Edit
+undo = Undo
+redo = Redo
+add-column = Add Column
+remove-column = Remove Column
+pick-screen = Pick from Screen
+tools = Tools
 view = View
 graphs = Graphs
 expanded = expanded
+compact = Compact mode
+always-on-top = Always on top
+color-managed = Color managed preview
+soft-proof = Soft proof
+proof-target = Proof target
+proof-cmyk = Print (CMYK profile)
+proof-web-safe = Web-safe palette
+proof-xterm = 256-color terminal
+soft-proof-of = As reproduced on {$target}
+out-of-gamut = Out of gamut on {$target}
+hdr-mode = HDR mode
+tone-mapped = Tone mapped for a {$peak} cd/m² peak
+luminance = Luminance: {$nits} cd/m²
+leave-compact = Leave compact mode
 menu-about = About
+pick-history = Pick History
+sampling = Sampling
+
+## Sampling
+sample-pixel = Single Pixel
+sample-average = Average
+sample-median = Median
+sample-dominant = Dominant Color
+sample-size = {$size}×{$size} Area
+sampled-area = Sampled area
+
+## Palette
+palette = Palette
+extract-palette-file = Extract Palette from Image…
+extract-palette-screenshot = Extract Palette from Screenshot…
+extract-palette-title = Choose an image
+palette-size = {$size} Colors
+palette-coverage = {$percent}% of the image
+palette-add = Add as a new column
+palette-add-all = Add All as Columns
+snap-palette = Snap to Palette
+snap-off = Off
+snap-xterm-16 = 16-color terminal
+snap-imported = Imported palette
+snap-match = ≈ {$name} in {$palette}, ΔE {$delta}
+snap-ansi = ANSI {$code}
+snap-copy = Copy the name or ANSI code
+snap-apply = Snap to this color
+import-palette = Import Palette…
+import-palette-title = Choose a palette
+palettes = Palettes
+export-sheet = Export Swatch Sheet…
+export-sheet-title = Export Swatch Sheet
+sheet-source = Colors
+sheet-all-columns = All columns
+sheet-active-column = Active column
+sheet-size = Swatch size
+sheet-per-row = Swatches per row
+sheet-background = Background
+sheet-white = White
+sheet-black = Black
+sheet-transparent = Transparent
+sheet-labels = Labels
+sheet-name = Nearest name
+sheet-export-svg = Export SVG
+sheet-export-png = Export PNG
+sheet-file-name = swatches
+
+## COSMIC Theme
+desktop-theme = COSMIC Theme…
+desktop-theme-title = COSMIC Theme
+read-desktop-theme = Read Desktop Colors
+set-accent-color = Set as Accent Color
+theme-dark = Dark theme
+theme-roles = Colors
+role-default = COSMIC default
+role-accent = Accent
+role-background = Background
+role-primary-container = Primary container
+role-secondary-container = Secondary container
+role-success = Success
+role-warning = Warning
+role-destructive = Destructive
+apply-desktop-theme = Apply to Desktop
+export-theme = Export Theme
+export-theme-title = Export COSMIC Theme
+cosmic-themes = COSMIC themes
+theme-file-name = theme
+
+## Settings
+settings = Settings
+settings-appearance = Appearance
+settings-theme = Theme
+theme-system = Match Desktop
+theme-dark = Dark
+theme-light = Light
+settings-colors = Colors
+settings-default-space = Color space of new columns
+settings-precision = Decimal places
+precision-auto = Automatic
+precision-places = {$places}
+settings-unit = Show 0–1 channels as
+unit-fraction = 0–1
+unit-percent = Percent
+unit-byte = 0–255
+unit-word = 0–65535
+unit-global = Default unit
+channel-invalid = Enter a value from {$min} to {$max}
+channel-invalid-min = Enter a value of at least {$min}
+settings-copy-format = Copy format
+copy-format-current = Column's color space
+settings-scroll-step = Slider scroll step
+settings-imported-palette = Imported palette
+imported-palette-none = None
+settings-display = Display
+settings-display-profile = Display profile
+display-profile-none = None, colors are not managed
+choose-display-profile = Choose the profile of your monitor
+settings-print = Print
+settings-cmyk-profile = CMYK profile
+cmyk-profile-none = None, naive conversion
+choose = Choose…
+choose-cmyk-profile = Choose a CMYK output profile
+icc-profiles = ICC profiles
+settings-rendering-intent = Rendering intent
+intent-perceptual = Perceptual
+intent-relative = Relative colorimetric
+intent-saturation = Saturation
+intent-absolute = Absolute colorimetric
+settings-ink-limit = Total ink limit
+ink-coverage = Total ink: { $coverage }%
+ink-over-limit = Above the { $limit }% ink limit of the printer
+settings-hdr = HDR
+settings-sdr-white = SDR white
+settings-hdr-peak = Peak of tone-mapped previews
+nits = {$nits} cd/m²
+settings-startup = On Startup
+
+## Keyboard Shortcuts
+keybinds = Keyboard Shortcuts
+keybind-help = Click a shortcut and press the new keys. Escape cancels, Backspace removes the shortcut.
+keybind-recording = Press keys…
+keybind-none = Disabled
+keybind-reset = Reset to default
+keybind-reset-all = Reset All
+keybind-conflict = {$shortcut} is already used by “{$action}”
+keybind-option = {$group}: {$option}
+copy = Copy
+copy-as = Copy as {$format}
+switch-space = Switch to {$space}
+
+## Named Colors
+named-colors = Named Colors
+named-search = Search by name
+named-empty = Type a name like “skyblue” or “navy blue”
+named-add = Add as a new column
+named-css = CSS
+named-x11 = X11
+nearest-named = ≈ {$name} ({$collection})
+delta-e = ΔE {$delta}
+
+## Pick History
+history-picked = Picked
+history-copied = Copied
+history-now = just now
+history-minutes = {$count} min ago
+history-hours = {$count} h ago
+history-days = {$count} d ago
+history-restore = Restore into a new column
+history-remove = Remove
+history-clear = Clear History
+history-empty = Picked and copied colors will appear here
+open-app = Open Color Picker
+
+## Columns
+duplicate = Duplicate
+lock = Lock
+unlock = Unlock
+link = Link with other columns
+unlink = Unlink
+
+## Adjustments
+adjust = Adjust
+op-lighten = Lighten
+op-darken = Darken
+op-saturate = Saturate
+op-desaturate = Desaturate
+op-rotate-hue = Hue {$degrees}°
+op-invert = Invert
+op-grayscale = Grayscale
+mix = Mix
+column = Column {$number}
+apply-in-place = Apply to this column
+apply-new-column = Apply as a new column
 
 ## About
 git-description = Git commit {$hash} on {$date}
@@ -15,6 +210,7 @@ hsv = HSV
 oklab = OKLAB
 oklch = OKLCH
 cmyk = CMYK
+hdr = HDR (Rec.2100)
 
 ## Colors
 red = Red
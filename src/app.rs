@@ -1,34 +1,180 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::colorspace::{ColorSpace, ColorSpaceCombo, ColorSpaceMessage};
+use crate::colorspace::{
+    self, ColorSpace, ColorSpaceCombo, ColorSpaceMessage, Conversion, Encoding, Intent, Operation,
+    Unit,
+};
+use crate::dbus;
+use crate::desktop::{self, Role};
+use crate::display::{self, DisplayProfile};
 use crate::fl;
+use crate::history::History;
+use crate::keybinds;
+use crate::named::{self, NamedColor};
+use crate::palette::{self, Swatch};
+use crate::pick_history::{Entry, EntryKind, PickHistory};
+use crate::portal;
+use crate::proof::{self, Proof};
+use crate::sampling::{self, Sample, SampleMode};
+use crate::settings::{self, AppTheme, Settings};
+use crate::sheet::{self, Sheet};
+use crate::snap;
 use crate::widgets::color_block;
+use ashpd::desktop::file_chooser::FileFilter;
 use cosmic::app::context_drawer::ContextDrawer;
-use cosmic::app::{Core, Task};
+use cosmic::app::{Core, CosmicFlags, Task};
+use cosmic::cosmic_config;
 use cosmic::iced::alignment::{Horizontal, Vertical};
-use cosmic::iced::keyboard::{Key, Modifiers};
+use cosmic::iced::keyboard::{key::Named, Key, Modifiers};
+use cosmic::iced::widget::image::FilterMethod;
+use cosmic::iced::widget::mouse_area;
+use cosmic::iced::widget::operation as widget_operation;
 use cosmic::iced::widget::scrollable::{Direction, Scrollbar};
-use cosmic::iced::{clipboard, Length};
-use cosmic::iced::{event, keyboard::Event as KeyEvent, Color, Event, Subscription};
+use cosmic::iced::window::{self, Level};
+use cosmic::iced::{clipboard, Length, Size};
+use cosmic::iced::{event, keyboard::Event as KeyEvent, mouse, Color, Event, Subscription};
 use cosmic::widget::menu::{self, action::MenuAction, MenuBar};
-use cosmic::{theme, widget, Application, ApplicationExt, Apply, Element};
+use cosmic::{dbus_activation, theme, widget, Application, ApplicationExt, Apply, Element};
 use log::info;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const CONFIG_VERSION: u64 = 1;
+pub(crate) const STATE_VERSION: u64 = 1;
+
+/// Window size of the compact mode
+const COMPACT_SIZE: Size = Size::new(300.0, 260.0);
+/// Window size restored when leaving the compact mode the app was started in
+const WINDOW_SIZE: Size = Size::new(1024.0, 768.0);
+
+/// Number of columns that can be chosen as a mix target
+const MIX_TARGETS: usize = 64;
+
+/// Number of columns that can be chosen for a theme role
+const ROLE_SOURCES: usize = 64;
 
 pub struct ColorPicker {
-    pub spaces: Vec<ColorSpace>,
+    pub columns: Vec<Column>,
     last_edited: usize,
     hex_edit: Option<(usize, String)>,
+    srgb_edit: Option<(usize, String)>,
+    dragging: Option<usize>,
+    /// Size of the window outside of the compact mode, restored when leaving it
+    window_size: Size,
+    history: History<Vec<Column>, Edit>,
+    pick_history: PickHistory,
+    sample_mode: SampleMode,
+    sample_size: u32,
+    loupe: Option<(usize, widget::image::Handle)>,
+    palette: Vec<Swatch>,
+    palette_size: usize,
+    sheet: Sheet,
+    /// Index into `sheet_sources`
+    sheet_source: usize,
+    /// Id of the column of each [`Role`], `None` keeps COSMIC's default
+    theme_roles: [Option<u64>; 7],
+    /// Whether the theme page targets the dark theme
+    theme_dark: bool,
+    operations: Option<usize>,
+    operation_target: usize,
+    /// Id of the column mixed into the active one
+    mix_with: Option<u64>,
+    mix_amount: f32,
+    mix_space: usize,
+    named_query: String,
+    named_results: Vec<&'static NamedColor>,
     show_graphs: bool,
     expanded: bool,
+    context_page: ContextPage,
 
     colorspace_selections: Vec<ColorSpaceCombo>,
     colorspace_names: Vec<String>,
+    operation_targets: Vec<String>,
+    column_names: Vec<String>,
+    precision_names: Vec<String>,
+    unit_names: Vec<String>,
+    /// The global unit followed by every unit, for a column's unit dropdown
+    column_unit_names: Vec<String>,
+    encoding_names: Vec<String>,
+    copy_format_names: Vec<String>,
+    scroll_step_names: Vec<String>,
+    theme_names: Vec<String>,
+    intent_names: Vec<String>,
+    ink_limit_names: Vec<String>,
+    sdr_white_names: Vec<String>,
+    hdr_peak_names: Vec<String>,
+    /// What new columns are converted with, e.g. the loaded CMYK profile
+    conversion: Conversion,
+    /// Profile of the monitor, used while color management is on
+    display_profile: Option<Rc<DisplayProfile>>,
+    /// Target the graphs are soft proofed on, `None` when soft proofing is off
+    proof: Option<Rc<Proof>>,
+    /// Palette imported in the settings, `None` until one is loaded
+    imported: Option<snap::Imported>,
+    sheet_sources: Vec<String>,
+    sheet_size_names: Vec<String>,
+    sheet_per_row_names: Vec<String>,
+    sheet_background_names: Vec<String>,
+    /// COSMIC's default followed by every column, for the theme roles
+    theme_sources: Vec<String>,
+    settings: Settings,
     keybinds: HashMap<menu::KeyBind, Action>,
+    keybind_overrides: HashMap<String, String>,
+    /// Index into [`keybinds::BINDABLE`] waiting for a new shortcut
+    recording: Option<usize>,
+    keybind_error: Option<String>,
+    config: Option<cosmic_config::Config>,
+    state: Option<cosmic_config::Config>,
+    /// Publishes the column colors to the D-Bus service
+    dbus: Option<dbus::Publisher>,
+    /// Colors last sent to the D-Bus service
+    published: Vec<String>,
     core: Core,
 }
 
+/// Id of the next column
+static NEXT_COLUMN: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone)]
+pub struct Column {
+    /// Stays the same when columns are moved, unlike the column's index
+    pub id: u64,
+    pub space: ColorSpace,
+    /// Locked columns ignore edits and picks
+    pub locked: bool,
+    /// Linked columns share the same color
+    pub linked: bool,
+}
+
+impl Column {
+    pub fn new(space: ColorSpace) -> Self {
+        Self {
+            id: NEXT_COLUMN.fetch_add(1, Ordering::Relaxed),
+            space,
+            locked: false,
+            linked: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ContextPage {
+    #[default]
+    About,
+    PickHistory,
+    Palette,
+    NamedColors,
+    Settings,
+    Keybinds,
+    ExportSheet,
+    DesktopTheme,
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     None,
@@ -42,6 +188,15 @@ pub enum Message {
     },
     AddSpace,
     RemoveSpace(usize),
+    RemoveActiveSpace,
+    DuplicateActiveSpace,
+    SwitchActiveSpace(ColorSpaceCombo),
+    DuplicateSpace(usize),
+    ToggleLock(usize),
+    ToggleLink(usize),
+    DragColumn(usize),
+    DragOver(usize),
+    DropColumn,
 
     EditHex {
         space: usize,
@@ -51,22 +206,194 @@ pub enum Message {
         space: usize,
         hex: String,
     },
+    EditSrgb {
+        space: usize,
+        srgb: String,
+    },
+    SubmitSrgb {
+        space: usize,
+        srgb: String,
+    },
+    ChangeUnit {
+        index: usize,
+        selected: usize,
+    },
+    ChangeEncoding {
+        index: usize,
+        selected: usize,
+    },
+
+    Undo,
+    Redo,
+
+    ToggleOperations(usize),
+    Operate(usize, Operation),
+    SetOperationTarget(usize),
+    SetMixWith(usize),
+    SetMixAmount(f32),
+    SetMixSpace(usize),
+    Mix(usize),
+
+    ExtractPalette(palette::Source),
+    ExtractPaletteResponse(Vec<Swatch>),
+    SetPaletteSize(usize),
+    AddSwatch(usize),
+    AddAllSwatches,
+
+    SetSheetSource(usize),
+    SetSheetSize(usize),
+    SetSheetPerRow(usize),
+    SetSheetBackground(usize),
+    ToggleSheetLabel(sheet::Label, bool),
+    ExportSheet(sheet::Format),
+    ReadDesktopTheme,
+    SetAccentColor,
+    SetThemeRole(usize, usize),
+    SetThemeDark(bool),
+    ApplyDesktopTheme,
+    ExportTheme,
+
+    SetDefaultSpace(usize),
+    SetPrecision(usize),
+    SetUnit(usize),
+    SetCopyFormat(usize),
+    SetScrollStep(usize),
+    SetStartupGraphs(bool),
+    SetStartupExpanded(bool),
+    SetAppTheme(usize),
+    ChooseCmykProfile,
+    SetCmykProfile(Option<PathBuf>),
+    SetRenderingIntent(usize),
+    SetInkLimit(usize),
+    SetSdrWhite(usize),
+    SetHdrPeak(usize),
+    ChooseDisplayProfile,
+    SetDisplayProfile(Option<PathBuf>),
+    ColordProfile(Option<PathBuf>),
+
+    RecordKeybind(usize),
+    ResetKeybind(usize),
+    ResetAllKeybinds,
+
+    SearchNamed(String),
+    AddNamed(usize),
+
+    RestoreHistoryEntry(usize),
+    RemoveHistoryEntry(usize),
+    ClearHistory,
 
     ToggleGraphs,
     ToggleExpanded,
-    ToggleAboutPage,
+    ToggleCompact,
+    ToggleAlwaysOnTop,
+    WindowResized(Size),
+    ToggleColorManaged,
+    ToggleSoftProof,
+    ToggleHdr,
+    SetProofTarget(proof::Target),
+    SetSnapPalette(Option<snap::Palette>),
+    ImportPalette,
+    SetImportedPalette(Option<PathBuf>),
+    SnapToPalette(usize),
+    CopySnap(usize),
+    SetSampleMode(SampleMode),
+    SetSampleSize(u32),
+    ToggleContextPage(ContextPage),
     LaunchUrl(String),
+    Dbus(dbus::Event),
 
     CopyToClipboard(usize),
+    CopyActive(CopyFormat),
     PickScreenRequest(usize),
-    PickScreenResponse((usize, ashpd::desktop::Color)),
+    PickActiveScreen,
+    /// Picked color for the column with the id
+    PickScreenResponse((u64, [f32; 3])),
+    SampleScreenResponse(u64, Sample),
     Key(Key, Modifiers),
 }
 
+/// Groups consecutive edits into a single undo step
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Edit {
+    Channel { space: usize, index: usize },
+    Hex(usize),
+    Srgb(usize),
+    Reorder,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CopyFormat {
+    /// The format of the column's color space
+    #[default]
+    Current,
+    Hex,
+    Space(ColorSpaceCombo),
+}
+
+impl CopyFormat {
+    /// Formats the color of `space`, `Current` uses the space's own format
+    pub fn format(self, space: &ColorSpace, conversion: &Conversion) -> String {
+        match self {
+            CopyFormat::Current => space.copy_to_clipboard(),
+            CopyFormat::Hex => {
+                let rgb = space.get_rgb();
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let srgb = [
+                    (rgb[0].clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (rgb[1].clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (rgb[2].clamp(0.0, 1.0) * 255.0).round() as u8,
+                ];
+                format!("#{}", hex::encode(srgb))
+            }
+            CopyFormat::Space(combo) => {
+                ColorSpace::from_combo(combo, space.get_rgb(), conversion).copy_to_clipboard()
+            }
+        }
+    }
+}
+
+impl Display for CopyFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CopyFormat::Current => f.write_str(&fl!("copy-format-current")),
+            CopyFormat::Hex => f.write_str("Hex"),
+            CopyFormat::Space(space) => space.fmt(f),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Action {
+    Undo,
+    Redo,
+    Copy(CopyFormat),
     ToggleGraphs,
     ToggleExpanded,
+    ToggleCompact,
+    AlwaysOnTop,
+    ColorManaged,
+    SoftProof,
+    HdrMode,
+    ProofTarget(proof::Target),
+    SnapPalette(Option<snap::Palette>),
+    ImportPalette,
+    SampleMode(SampleMode),
+    SampleSize(u32),
+    ExtractPalette(palette::Source),
+    PaletteSize(usize),
+    AddColumn,
+    RemoveColumn,
+    DuplicateColumn,
+    SwitchSpace(ColorSpaceCombo),
+    PickScreen,
+    NamedColors,
+    ExportSheet,
+    DesktopTheme,
+    ReadDesktopTheme,
+    SetAccentColor,
+    Settings,
+    Keybinds,
+    PickHistory,
     About,
 }
 
@@ -75,17 +402,73 @@ impl MenuAction for Action {
 
     fn message(&self) -> Message {
         match self {
+            Action::Undo => Message::Undo,
+            Action::Redo => Message::Redo,
+            Action::Copy(format) => Message::CopyActive(*format),
             Action::ToggleGraphs => Message::ToggleGraphs,
             Action::ToggleExpanded => Message::ToggleExpanded,
-            Action::About => Message::ToggleAboutPage,
+            Action::ToggleCompact => Message::ToggleCompact,
+            Action::AlwaysOnTop => Message::ToggleAlwaysOnTop,
+            Action::ColorManaged => Message::ToggleColorManaged,
+            Action::SoftProof => Message::ToggleSoftProof,
+            Action::HdrMode => Message::ToggleHdr,
+            Action::ProofTarget(target) => Message::SetProofTarget(*target),
+            Action::SnapPalette(palette) => Message::SetSnapPalette(*palette),
+            Action::ImportPalette => Message::ImportPalette,
+            Action::SampleMode(mode) => Message::SetSampleMode(*mode),
+            Action::SampleSize(size) => Message::SetSampleSize(*size),
+            Action::ExtractPalette(source) => Message::ExtractPalette(*source),
+            Action::PaletteSize(size) => Message::SetPaletteSize(*size),
+            Action::AddColumn => Message::AddSpace,
+            Action::RemoveColumn => Message::RemoveActiveSpace,
+            Action::DuplicateColumn => Message::DuplicateActiveSpace,
+            Action::SwitchSpace(space) => Message::SwitchActiveSpace(*space),
+            Action::PickScreen => Message::PickActiveScreen,
+            Action::NamedColors => Message::ToggleContextPage(ContextPage::NamedColors),
+            Action::ExportSheet => Message::ToggleContextPage(ContextPage::ExportSheet),
+            Action::DesktopTheme => Message::ToggleContextPage(ContextPage::DesktopTheme),
+            Action::ReadDesktopTheme => Message::ReadDesktopTheme,
+            Action::SetAccentColor => Message::SetAccentColor,
+            Action::Settings => Message::ToggleContextPage(ContextPage::Settings),
+            Action::Keybinds => Message::ToggleContextPage(ContextPage::Keybinds),
+            Action::PickHistory => Message::ToggleContextPage(ContextPage::PickHistory),
+            Action::About => Message::ToggleContextPage(ContextPage::About),
         }
     }
 }
 
+/// Colors given on the command line
+#[derive(Clone, Debug, Default)]
+pub struct Flags {
+    /// Colors to open as columns
+    pub colors: Vec<[f32; 3]>,
+    /// The colors as hex, sent to a running instance as the action to activate
+    action: Option<String>,
+}
+
+impl Flags {
+    pub fn new(colors: Vec<[f32; 3]>) -> Self {
+        let action = (!colors.is_empty()).then(|| {
+            let hex: Vec<String> = colors.iter().map(|rgb| dbus::to_hex(*rgb)).collect();
+            hex.join(" ")
+        });
+        Self { colors, action }
+    }
+}
+
+impl CosmicFlags for Flags {
+    type SubCommand = String;
+    type Args = Vec<String>;
+
+    fn action(&self) -> Option<&String> {
+        self.action.as_ref()
+    }
+}
+
 impl Application for ColorPicker {
     type Executor = cosmic::executor::Default;
 
-    type Flags = ();
+    type Flags = Flags;
 
     type Message = Message;
 
@@ -100,57 +483,171 @@ impl Application for ColorPicker {
     }
 
     fn header_start(&self) -> Vec<Element<'_, Self::Message>> {
-        vec![MenuBar::new(vec![menu::Tree::with_children(
-            widget::RcElementWrapper::new(menu::root(fl!("view")).into()),
-            menu::items(
-                &self.keybinds,
-                vec![
-                    menu::Item::CheckBox(
-                        fl!("graphs"),
-                        None,
-                        self.show_graphs,
-                        Action::ToggleGraphs,
-                    ),
-                    menu::Item::CheckBox(
-                        fl!("expanded"),
-                        None,
-                        self.expanded,
-                        Action::ToggleExpanded,
-                    ),
-                    menu::Item::Button(fl!("menu-about"), None, Action::About),
-                ],
+        // The compact window has no room for the menus
+        if self.settings.compact {
+            return Vec::new();
+        }
+
+        let undo = if self.history.can_undo() {
+            menu::Item::Button(fl!("undo"), None, Action::Undo)
+        } else {
+            menu::Item::ButtonDisabled(fl!("undo"), None, Action::Undo)
+        };
+        let redo = if self.history.can_redo() {
+            menu::Item::Button(fl!("redo"), None, Action::Redo)
+        } else {
+            menu::Item::ButtonDisabled(fl!("redo"), None, Action::Redo)
+        };
+
+        vec![MenuBar::new(vec![
+            menu::Tree::with_children(
+                widget::RcElementWrapper::new(menu::root(fl!("edit")).into()),
+                menu::items(
+                    &self.keybinds,
+                    vec![
+                        undo,
+                        redo,
+                        menu::Item::Divider,
+                        menu::Item::Button(fl!("add-column"), None, Action::AddColumn),
+                        menu::Item::Button(fl!("remove-column"), None, Action::RemoveColumn),
+                        menu::Item::Button(fl!("pick-screen"), None, Action::PickScreen),
+                    ],
+                ),
+            ),
+            menu::Tree::with_children(
+                widget::RcElementWrapper::new(menu::root(fl!("tools")).into()),
+                menu::items(&self.keybinds, self.tools_items()),
+            ),
+            menu::Tree::with_children(
+                widget::RcElementWrapper::new(menu::root(fl!("view")).into()),
+                menu::items(
+                    &self.keybinds,
+                    vec![
+                        menu::Item::CheckBox(
+                            fl!("graphs"),
+                            None,
+                            self.show_graphs,
+                            Action::ToggleGraphs,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("expanded"),
+                            None,
+                            self.expanded,
+                            Action::ToggleExpanded,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("compact"),
+                            None,
+                            self.settings.compact,
+                            Action::ToggleCompact,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("always-on-top"),
+                            None,
+                            self.settings.always_on_top,
+                            Action::AlwaysOnTop,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("color-managed"),
+                            None,
+                            self.settings.color_managed,
+                            Action::ColorManaged,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("soft-proof"),
+                            None,
+                            self.settings.soft_proof,
+                            Action::SoftProof,
+                        ),
+                        menu::Item::Folder(fl!("proof-target"), self.proof_items()),
+                        menu::Item::CheckBox(
+                            fl!("hdr-mode"),
+                            None,
+                            self.settings.hdr,
+                            Action::HdrMode,
+                        ),
+                        menu::Item::Folder(fl!("sampling"), self.sampling_items()),
+                        menu::Item::Button(fl!("pick-history"), None, Action::PickHistory),
+                        menu::Item::Button(fl!("settings"), None, Action::Settings),
+                        menu::Item::Button(fl!("menu-about"), None, Action::About),
+                    ],
+                ),
             ),
-        )])
+        ])
         .into()]
     }
 
     fn header_center(&self) -> Vec<Element<'_, Self::Message>> {
+        if self.settings.compact {
+            return Vec::new();
+        }
+
         vec![widget::text::heading(fl!("app-title")).into()]
     }
 
-    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Self::Message>) {
-        let mut keybinds = HashMap::new();
-        keybinds.insert(
-            menu::KeyBind {
-                modifiers: vec![menu::key_bind::Modifier::Ctrl],
-                key: Key::Character("g".into()),
-            },
-            Action::ToggleGraphs,
-        );
-        keybinds.insert(
-            menu::KeyBind {
-                modifiers: vec![menu::key_bind::Modifier::Ctrl],
-                key: Key::Character("e".into()),
-            },
-            Action::ToggleExpanded,
-        );
+    fn init(core: Core, flags: Self::Flags) -> (Self, Task<Self::Message>) {
+        let state = match cosmic_config::Config::new_state(Self::APP_ID, STATE_VERSION) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                log::error!("Failed to open state: {e}");
+                None
+            }
+        };
+        let pick_history = state.as_ref().map(PickHistory::load).unwrap_or_default();
+
+        let config = match cosmic_config::Config::new(Self::APP_ID, CONFIG_VERSION) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                log::error!("Failed to open config: {e}");
+                None
+            }
+        };
+        let keybind_overrides = config.as_ref().map(keybinds::load).unwrap_or_default();
+        let keybinds = keybinds::build(&keybind_overrides);
+        let mut settings = config.as_ref().map(Settings::load).unwrap_or_default();
+        let conversion = settings.conversion();
 
         let mut app = ColorPicker {
-            spaces: vec![ColorSpace::default()],
+            columns: if flags.colors.is_empty() {
+                vec![[1.0; 3]]
+            } else {
+                flags.colors
+            }
+            .into_iter()
+            .map(|rgb| {
+                Column::new(ColorSpace::from_combo(
+                    settings.default_space,
+                    rgb,
+                    &conversion,
+                ))
+            })
+            .collect(),
             last_edited: 0,
             hex_edit: None,
-            show_graphs: false,
-            expanded: false,
+            srgb_edit: None,
+            dragging: None,
+            window_size: WINDOW_SIZE,
+            history: History::default(),
+            pick_history,
+            sample_mode: SampleMode::default(),
+            sample_size: sampling::SIZES[0],
+            loupe: None,
+            palette: Vec::new(),
+            palette_size: palette::SIZES[1],
+            sheet: Sheet::default(),
+            sheet_source: 0,
+            theme_roles: [None; 7],
+            theme_dark: desktop::is_dark(),
+            operations: None,
+            operation_target: 0,
+            mix_with: None,
+            mix_amount: 50.0,
+            mix_space: 3,
+            named_query: String::new(),
+            named_results: Vec::new(),
+            show_graphs: settings.show_graphs,
+            expanded: settings.expanded,
+            context_page: ContextPage::default(),
 
             colorspace_selections: vec![
                 ColorSpaceCombo::Rgb,
@@ -158,9 +655,77 @@ impl Application for ColorPicker {
                 ColorSpaceCombo::Oklab,
                 ColorSpaceCombo::Oklch,
                 ColorSpaceCombo::Cmyk,
+                ColorSpaceCombo::Hdr,
             ],
             colorspace_names: vec![],
+            operation_targets: vec![fl!("apply-in-place"), fl!("apply-new-column")],
+            column_names: (1..=MIX_TARGETS)
+                .map(|number| fl!("column", number = number))
+                .collect(),
+            precision_names: settings::PRECISIONS
+                .into_iter()
+                .map(|precision| match precision {
+                    Some(places) => fl!("precision-places", places = places),
+                    None => fl!("precision-auto"),
+                })
+                .collect(),
+            unit_names: Unit::ALL.iter().map(ToString::to_string).collect(),
+            column_unit_names: std::iter::once(fl!("unit-global"))
+                .chain(Unit::ALL.iter().map(ToString::to_string))
+                .collect(),
+            encoding_names: Encoding::ALL.iter().map(ToString::to_string).collect(),
+            copy_format_names: settings::COPY_FORMATS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            scroll_step_names: settings::SCROLL_STEPS
+                .into_iter()
+                .map(|step| format!("{}%", (step * 1000.0).round() / 10.0))
+                .collect(),
+            theme_names: AppTheme::ALL.iter().map(ToString::to_string).collect(),
+            intent_names: Intent::ALL.iter().map(ToString::to_string).collect(),
+            ink_limit_names: settings::INK_LIMITS
+                .iter()
+                .map(|limit| format!("{limit}%"))
+                .collect(),
+            sdr_white_names: settings::SDR_WHITES
+                .iter()
+                .map(|nits| fl!("nits", nits = *nits))
+                .collect(),
+            hdr_peak_names: settings::HDR_PEAKS
+                .iter()
+                .map(|nits| fl!("nits", nits = *nits))
+                .collect(),
+            conversion,
+            display_profile: None,
+            proof: None,
+            imported: None,
+            sheet_sources: vec![
+                fl!("sheet-all-columns"),
+                fl!("sheet-active-column"),
+                fl!("palette"),
+            ],
+            sheet_size_names: sheet::SIZES
+                .iter()
+                .map(|size| format!("{size} px"))
+                .collect(),
+            sheet_per_row_names: sheet::PER_ROW.iter().map(ToString::to_string).collect(),
+            sheet_background_names: sheet::Background::ALL
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            theme_sources: std::iter::once(fl!("role-default"))
+                .chain((1..=ROLE_SOURCES).map(|number| fl!("column", number = number)))
+                .collect(),
+            settings,
             keybinds,
+            keybind_overrides,
+            recording: None,
+            keybind_error: None,
+            config,
+            state,
+            dbus: None,
+            published: Vec::new(),
             core,
         };
 
@@ -169,40 +734,152 @@ impl Application for ColorPicker {
             .iter()
             .map(ToString::to_string)
             .collect();
+        app.apply_proof();
+        if app.settings.imported_palette.is_some() {
+            app.load_imported_palette();
+        }
 
-        let command = app.set_window_title(fl!("app-title"));
+        let mut command = Task::batch([
+            app.set_window_title(fl!("app-title")),
+            cosmic::command::set_theme(app.settings.app_theme.theme()),
+        ]);
+        if app.settings.compact {
+            command = command.chain(app.window_mode());
+        }
+        if app.settings.color_managed {
+            command = command.chain(app.load_display_profile());
+        }
         (app, command)
     }
 
     fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
         match message {
             Message::None => (),
-            Message::ColorSpace { index: i, message } => match message {
-                ColorSpaceMessage::ChangeValue { index, value } => {
-                    self.spaces[i].change_value(index, value);
-                }
-                ColorSpaceMessage::ChangeString { index, string } => {
-                    self.spaces[i].change_string(index, string);
+            Message::ColorSpace { index: i, .. } if self.is_locked(i) => (),
+            Message::ColorSpace { index: i, message } => {
+                self.last_edited = i;
+                match message {
+                    ColorSpaceMessage::ChangeValue { index, value } => {
+                        self.checkpoint(Some(Edit::Channel { space: i, index }));
+                        self.columns[i].space.change_value(index, value);
+                    }
+                    ColorSpaceMessage::ChangeString { index, string } => {
+                        self.checkpoint(Some(Edit::Channel { space: i, index }));
+                        let ctx = self.settings.context();
+                        self.columns[i].space.change_string(&ctx, index, string);
+                    }
+                    ColorSpaceMessage::SubmitString { index } => {
+                        self.checkpoint(Some(Edit::Channel { space: i, index }));
+                        let ctx = self.settings.context();
+                        self.columns[i].space.submit_string(&ctx, index);
+                        self.history.seal();
+                    }
                 }
-            },
+
+                self.sync_linked(i);
+            }
+            Message::ChangeColorSpace { index, .. } if self.is_locked(index) => (),
             Message::ChangeColorSpace { index, selected } => {
-                self.spaces[index] = match self.colorspace_selections[selected] {
-                    ColorSpaceCombo::Rgb => self.spaces[index].to_rgb(),
-                    ColorSpaceCombo::Hsv => self.spaces[index].to_hsv(),
-                    ColorSpaceCombo::Oklab => self.spaces[index].to_oklab(),
-                    ColorSpaceCombo::Oklch => self.spaces[index].to_oklch(),
-                    ColorSpaceCombo::Cmyk => self.spaces[index].to_cmyk(),
+                self.checkpoint(None);
+                let space = &mut self.columns[index].space;
+                *space = match self.colorspace_selections[selected] {
+                    ColorSpaceCombo::Rgb => space.to_rgb(),
+                    ColorSpaceCombo::Hsv => space.to_hsv(),
+                    ColorSpaceCombo::Oklab => space.to_oklab(),
+                    ColorSpaceCombo::Oklch => space.to_oklch(),
+                    ColorSpaceCombo::Cmyk => space.to_cmyk(&self.conversion),
+                    ColorSpaceCombo::Hdr => space.to_hdr(&self.conversion),
                 };
             }
             Message::AddSpace => {
-                self.spaces.push(ColorSpace::default());
+                self.checkpoint(None);
+                self.columns.push(self.new_column([1.0; 3]));
+                self.last_edited = self.columns.len() - 1;
             }
+            Message::RemoveSpace(index) if self.is_locked(index) => (),
             Message::RemoveSpace(index) => {
-                self.spaces.remove(index);
+                self.checkpoint(None);
+                let removed = self.columns.remove(index);
+                if self.mix_with == Some(removed.id) {
+                    self.mix_with = None;
+                }
+                self.last_edited = self.last_edited.min(self.columns.len().saturating_sub(1));
+                self.hex_edit = None;
+                self.srgb_edit = None;
+                self.loupe = None;
+            }
+            Message::RemoveActiveSpace => {
+                if self.last_edited < self.columns.len() {
+                    return self.update(Message::RemoveSpace(self.last_edited));
+                }
+            }
+            Message::DuplicateActiveSpace => {
+                if self.last_edited < self.columns.len() {
+                    return self.update(Message::DuplicateSpace(self.last_edited));
+                }
+            }
+            Message::SwitchActiveSpace(space) => {
+                let selected = self.colorspace_selections.iter().position(|s| *s == space);
+                if let Some(selected) = selected.filter(|_| self.last_edited < self.columns.len()) {
+                    return self.update(Message::ChangeColorSpace {
+                        index: self.last_edited,
+                        selected,
+                    });
+                }
+            }
+            Message::DuplicateSpace(index) => {
+                self.checkpoint(None);
+                let space = self.columns[index].space.clone();
+                self.columns.insert(index + 1, Column::new(space));
+                self.hex_edit = None;
+                self.srgb_edit = None;
+                self.loupe = None;
+            }
+            Message::ToggleLock(index) => {
+                self.checkpoint(None);
+                self.columns[index].locked = !self.columns[index].locked;
+            }
+            Message::ToggleLink(index) => {
+                self.checkpoint(None);
+                let linked = !self.columns[index].linked;
+                self.columns[index].linked = linked;
+
+                // Join the color of an existing group
+                let group = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .find(|(i, column)| *i != index && column.linked)
+                    .map(|(_, column)| column.space.get_rgb());
+                if let Some(rgb) = group.filter(|_| linked && !self.columns[index].locked) {
+                    self.columns[index].space.sync_from_rgb(rgb);
+                }
+            }
+            Message::DragColumn(index) => {
+                self.dragging = Some(index);
+            }
+            Message::DragOver(target) => {
+                if let Some(from) = self.dragging.filter(|from| *from != target) {
+                    self.checkpoint(Some(Edit::Reorder));
+                    let column = self.columns.remove(from);
+                    self.columns.insert(target, column);
+                    self.dragging = Some(target);
+                    self.hex_edit = None;
+                    self.srgb_edit = None;
+                    self.loupe = None;
+                }
+            }
+            Message::DropColumn => {
+                if self.dragging.take().is_some() {
+                    self.history.seal();
+                }
             }
 
+            Message::EditHex { space, .. } if self.is_locked(space) => (),
             Message::EditHex { space, hex } => {
+                self.last_edited = space;
                 self.hex_edit = Some((space, hex.clone()));
+                self.srgb_edit = None;
 
                 if hex.is_empty() {
                     return Task::none();
@@ -215,7 +892,11 @@ impl Application for ColorPicker {
                             srgb[1] as f32 / 255.0,
                             srgb[2] as f32 / 255.0,
                         ];
-                        self.spaces[space].convert_from_rgb([rgb[0], rgb[1], rgb[2]]);
+                        self.checkpoint(Some(Edit::Hex(space)));
+                        self.columns[space]
+                            .space
+                            .convert_from_rgb([rgb[0], rgb[1], rgb[2]]);
+                        self.sync_linked(space);
                     }
                 } else {
                     // Invalid Hex
@@ -223,6 +904,51 @@ impl Application for ColorPicker {
             }
             Message::SubmitHex { .. } => {
                 self.hex_edit = None;
+                self.history.seal();
+            }
+
+            Message::EditSrgb { space, .. } if self.is_locked(space) => (),
+            Message::EditSrgb { space, srgb } => {
+                self.last_edited = space;
+                if let Some(rgb) = colorspace::parse_srgb(&srgb) {
+                    self.checkpoint(Some(Edit::Srgb(space)));
+                    self.columns[space].space.convert_from_rgb(rgb);
+                    self.sync_linked(space);
+                }
+                self.srgb_edit = Some((space, srgb));
+                self.hex_edit = None;
+            }
+            Message::SubmitSrgb { .. } => {
+                self.srgb_edit = None;
+                self.history.seal();
+            }
+
+            Message::ChangeUnit { index, .. } if self.is_locked(index) => (),
+            Message::ChangeUnit { index, selected } => {
+                self.checkpoint(None);
+                // The first entry follows the global unit
+                let unit = selected.checked_sub(1).map(|selected| Unit::ALL[selected]);
+                self.columns[index].space.set_unit(unit);
+            }
+            Message::ChangeEncoding { index, .. } if self.is_locked(index) => (),
+            Message::ChangeEncoding { index, selected } => {
+                self.checkpoint(None);
+                self.columns[index]
+                    .space
+                    .set_encoding(Encoding::ALL[selected]);
+            }
+
+            Message::Undo => {
+                if self.history.undo(&mut self.columns) {
+                    self.hex_edit = None;
+                    self.srgb_edit = None;
+                }
+            }
+            Message::Redo => {
+                if self.history.redo(&mut self.columns) {
+                    self.hex_edit = None;
+                    self.srgb_edit = None;
+                }
             }
 
             Message::ToggleGraphs => {
@@ -231,88 +957,576 @@ impl Application for ColorPicker {
             Message::ToggleExpanded => {
                 self.expanded = !self.expanded;
             }
-            Message::ToggleAboutPage => {
-                self.core.window.show_context = !self.core.window.show_context;
+            Message::ToggleCompact => {
+                self.settings.compact = !self.settings.compact;
+                self.save_settings();
+                return self.window_mode();
             }
-            Message::LaunchUrl(url) => match open::that_detached(&url) {
-                Ok(()) => {}
-                Err(e) => {
-                    log::warn!("Failed to open {:?}: {}", url, e);
+            Message::ToggleAlwaysOnTop => {
+                self.settings.always_on_top = !self.settings.always_on_top;
+                self.save_settings();
+                if self.settings.compact {
+                    return self.window_mode();
                 }
-            },
+            }
+            Message::WindowResized(size) => {
+                if !self.settings.compact {
+                    self.window_size = size;
+                }
+            }
+            Message::ToggleColorManaged => {
+                self.settings.color_managed = !self.settings.color_managed;
+                self.save_settings();
+                if self.settings.color_managed && self.display_profile.is_none() {
+                    return self.load_display_profile();
+                }
+            }
+            Message::ToggleSoftProof => {
+                self.settings.soft_proof = !self.settings.soft_proof;
+                self.apply_proof();
+                self.save_settings();
+            }
+            Message::ToggleHdr => {
+                self.settings.hdr = !self.settings.hdr;
+                self.save_settings();
+            }
+            Message::SetProofTarget(target) => {
+                self.settings.proof_target = target;
+                self.settings.soft_proof = true;
+                self.apply_proof();
+                self.save_settings();
+            }
+            Message::SetSnapPalette(palette) => {
+                self.settings.snap_palette = palette;
+                self.save_settings();
+            }
+            Message::ImportPalette => {
+                return cosmic::task::future(async move {
+                    let filter = FileFilter::new(&fl!("palettes"))
+                        .glob("*.gpl")
+                        .glob("*.txt")
+                        .glob("*.hex");
+                    match portal::open_file(&fl!("import-palette-title"), filter).await {
+                        Some(path) => Message::SetImportedPalette(Some(path)),
+                        None => Message::None,
+                    }
+                });
+            }
+            Message::SetImportedPalette(path) => {
+                self.settings.imported_palette = path;
+                self.load_imported_palette();
+                self.settings.snap_palette = match self.settings.imported_palette {
+                    Some(_) => Some(snap::Palette::Imported),
+                    None => self
+                        .settings
+                        .snap_palette
+                        .filter(|palette| *palette != snap::Palette::Imported),
+                };
+                self.save_settings();
+            }
+            Message::SnapToPalette(index) => {
+                let nearest = self.settings.snap_palette.and_then(|palette| {
+                    snap::nearest(
+                        palette,
+                        self.columns[index].space.get_rgb(),
+                        self.imported.as_ref(),
+                    )
+                });
+                if let Some((entry, _)) = nearest.filter(|_| !self.columns[index].locked) {
+                    self.checkpoint(None);
+                    self.columns[index].space.convert_from_rgb(entry.rgb);
+                    self.sync_linked(index);
+                }
+            }
+            Message::CopySnap(index) => {
+                let nearest = self.settings.snap_palette.and_then(|palette| {
+                    snap::nearest(
+                        palette,
+                        self.columns[index].space.get_rgb(),
+                        self.imported.as_ref(),
+                    )
+                });
+                if let Some((entry, _)) = nearest {
+                    let contents = entry.ansi.unwrap_or(entry.name);
+                    info!("Copying \"{}\" to clipboard", contents);
+                    return clipboard::write(contents);
+                }
+            }
+            Message::ToggleOperations(index) => {
+                self.operations = if self.operations == Some(index) {
+                    None
+                } else {
+                    Some(index)
+                };
+            }
+            Message::Operate(index, operation) => {
+                let rgb = operation.apply(self.columns[index].space.get_rgb());
+                self.apply_rgb(index, rgb);
+            }
+            Message::SetOperationTarget(target) => {
+                self.operation_target = target;
+            }
+            Message::SetMixWith(index) => {
+                self.mix_with = self.columns.get(index).map(|column| column.id);
+            }
+            Message::SetMixAmount(amount) => {
+                self.mix_amount = amount;
+            }
+            Message::SetMixSpace(selected) => {
+                self.mix_space = selected;
+            }
+            Message::Mix(index) => {
+                let other = self.mix_with.and_then(|id| self.column_index(id));
+                if let Some(other) = other {
+                    let rgb = colorspace::mix(
+                        self.columns[index].space.get_rgb(),
+                        self.columns[other].space.get_rgb(),
+                        self.mix_amount / 100.0,
+                        self.colorspace_selections[self.mix_space],
+                        &self.conversion,
+                    );
+                    self.apply_rgb(index, rgb);
+                }
+            }
 
-            Message::CopyToClipboard(index) => {
-                return self.copy_to_clipboard(index);
+            Message::ExtractPalette(source) => {
+                let k = self.palette_size;
+                return cosmic::task::future(async move {
+                    match palette::extract_from(source, k).await {
+                        Some(swatches) => Message::ExtractPaletteResponse(swatches),
+                        None => Message::None,
+                    }
+                });
             }
-            Message::PickScreenRequest(index) => {
+            Message::ExtractPaletteResponse(swatches) => {
+                self.palette = swatches;
+                self.context_page = ContextPage::Palette;
+                self.core.window.show_context = true;
+            }
+            Message::SetPaletteSize(size) => {
+                self.palette_size = size;
+            }
+            Message::AddSwatch(index) => {
+                if let Some(swatch) = self.palette.get(index) {
+                    let column = self.new_column(swatch.rgb);
+                    self.checkpoint(None);
+                    self.columns.push(column);
+                }
+            }
+            Message::AddAllSwatches => {
+                self.checkpoint(None);
+                let columns: Vec<_> = self
+                    .palette
+                    .iter()
+                    .map(|swatch| self.new_column(swatch.rgb))
+                    .collect();
+                self.columns.extend(columns);
+            }
+
+            Message::SetSheetSource(selected) => {
+                self.sheet_source = selected;
+            }
+            Message::SetSheetSize(selected) => {
+                self.sheet.size = sheet::SIZES[selected];
+            }
+            Message::SetSheetPerRow(selected) => {
+                self.sheet.per_row = sheet::PER_ROW[selected];
+            }
+            Message::SetSheetBackground(selected) => {
+                self.sheet.background = sheet::Background::ALL[selected];
+            }
+            Message::ToggleSheetLabel(label, shown) => {
+                self.sheet.labels.retain(|l| *l != label);
+                if shown {
+                    self.sheet.labels.push(label);
+                    // Keep labels in a stable order
+                    self.sheet
+                        .labels
+                        .sort_by_key(|l| sheet::Label::ALL.iter().position(|a| a == l));
+                }
+            }
+            Message::ExportSheet(format) => {
+                let sheet = self.sheet.clone();
+                let colors = self.sheet_colors();
                 return cosmic::task::future(async move {
-                    let req = ashpd::desktop::Color::pick().send().await;
-                    let Ok(req) = req else {
-                        log::error!("{req:?}");
+                    let name = format!("{}.{}", fl!("sheet-file-name"), format.extension());
+                    let filter = FileFilter::new(format.extension()).mimetype(format.mimetype());
+                    let Some(path) =
+                        portal::save_file(&fl!("export-sheet-title"), &name, filter).await
+                    else {
                         return Message::None;
                     };
 
-                    let result = req.response();
-                    let Ok(color) = result else {
-                        log::error!("{result:?}");
+                    let contents = match format {
+                        sheet::Format::Png => sheet.png(&colors),
+                        sheet::Format::Svg => Some(sheet.svg(&colors).into_bytes()),
+                    };
+                    if let Some(contents) = contents {
+                        if let Err(e) = std::fs::write(&path, contents) {
+                            log::error!("Failed to write {path:?}: {e}");
+                        }
+                    }
+                    Message::None
+                });
+            }
+
+            Message::ReadDesktopTheme => {
+                let colors = desktop::read();
+                if !colors.is_empty() {
+                    self.checkpoint(None);
+                }
+                // Map the roles to the new columns, so they can be edited and applied back
+                for (role, rgb) in colors {
+                    let column = self.new_column(rgb);
+                    let id = column.id;
+                    self.columns.push(column);
+                    let role = Role::ALL.iter().position(|r| *r == role);
+                    if let Some(i) = role.filter(|_| self.columns.len() <= ROLE_SOURCES) {
+                        self.theme_roles[i] = Some(id);
+                    }
+                }
+            }
+            Message::SetAccentColor => {
+                if let Some(column) = self.columns.get(self.last_edited) {
+                    desktop::set_accent(column.space.get_rgb());
+                }
+            }
+            Message::SetThemeRole(role, selected) => {
+                // The first source keeps COSMIC's default
+                self.theme_roles[role] = selected
+                    .checked_sub(1)
+                    .and_then(|index| self.columns.get(index))
+                    .map(|column| column.id);
+            }
+            Message::SetThemeDark(dark) => {
+                self.theme_dark = dark;
+            }
+            Message::ApplyDesktopTheme => {
+                desktop::apply(self.theme_dark, &self.theme_colors());
+            }
+            Message::ExportTheme => {
+                let Some(ron) =
+                    desktop::to_ron(&desktop::builder(self.theme_dark, &self.theme_colors()))
+                else {
+                    return Task::none();
+                };
+                return cosmic::task::future(async move {
+                    let filter = FileFilter::new(&fl!("cosmic-themes")).glob("*.ron");
+                    let Some(path) = portal::save_file(
+                        &fl!("export-theme-title"),
+                        &format!("{}.ron", fl!("theme-file-name")),
+                        filter,
+                    )
+                    .await
+                    else {
                         return Message::None;
                     };
 
-                    Message::PickScreenResponse((index, color))
+                    if let Err(e) = std::fs::write(&path, ron) {
+                        log::error!("Failed to write {path:?}: {e}");
+                    }
+                    Message::None
                 });
             }
-            Message::PickScreenResponse((index, color)) => {
-                let (r, g, b) = (color.red(), color.green(), color.blue());
 
-                #[allow(clippy::cast_possible_truncation)]
-                self.spaces[index].convert_from_rgb([r as f32, g as f32, b as f32]);
+            Message::SetDefaultSpace(selected) => {
+                self.settings.default_space = self.colorspace_selections[selected];
+                self.save_settings();
             }
-            Message::Key(key, modifiers) => {
-                for (key_bind, action) in &self.keybinds {
-                    if key_bind.matches(modifiers, &key) {
-                        return self.update(action.message());
+            Message::SetPrecision(selected) => {
+                self.settings.precision = settings::PRECISIONS[selected];
+                self.save_settings();
+            }
+            Message::SetUnit(selected) => {
+                self.settings.unit = Unit::ALL[selected];
+                for column in &mut self.columns {
+                    column.space.reset_fields();
+                }
+                self.save_settings();
+            }
+            Message::SetCopyFormat(selected) => {
+                self.settings.copy_format = settings::COPY_FORMATS[selected];
+                self.save_settings();
+            }
+            Message::SetScrollStep(selected) => {
+                self.settings.scroll_step = settings::SCROLL_STEPS[selected];
+                self.save_settings();
+            }
+            Message::SetStartupGraphs(show_graphs) => {
+                self.settings.show_graphs = show_graphs;
+                self.save_settings();
+            }
+            Message::SetStartupExpanded(expanded) => {
+                self.settings.expanded = expanded;
+                self.save_settings();
+            }
+            Message::SetAppTheme(selected) => {
+                self.settings.app_theme = AppTheme::ALL[selected];
+                self.save_settings();
+                return cosmic::command::set_theme(self.settings.app_theme.theme());
+            }
+            Message::ChooseCmykProfile => {
+                return cosmic::task::future(async move {
+                    let filter = FileFilter::new(&fl!("icc-profiles"))
+                        .mimetype("application/vnd.iccprofile")
+                        .glob("*.icc")
+                        .glob("*.icm");
+                    match portal::open_file(&fl!("choose-cmyk-profile"), filter).await {
+                        Some(path) => Message::SetCmykProfile(Some(path)),
+                        None => Message::None,
+                    }
+                });
+            }
+            Message::SetCmykProfile(path) => {
+                self.settings.cmyk_profile = path;
+                self.load_cmyk_profile();
+                self.save_settings();
+            }
+            Message::SetRenderingIntent(selected) => {
+                self.settings.rendering_intent = Intent::ALL[selected];
+                self.load_cmyk_profile();
+                self.save_settings();
+            }
+            Message::SetInkLimit(selected) => {
+                self.settings.ink_limit = settings::INK_LIMITS[selected];
+                self.save_settings();
+            }
+            Message::SetSdrWhite(selected) => {
+                self.settings.sdr_white = settings::SDR_WHITES[selected];
+                self.set_sdr_white();
+                self.save_settings();
+            }
+            Message::SetHdrPeak(selected) => {
+                self.settings.hdr_peak = settings::HDR_PEAKS[selected];
+                self.save_settings();
+            }
+            Message::ChooseDisplayProfile => {
+                return cosmic::task::future(async move {
+                    let filter = FileFilter::new(&fl!("icc-profiles"))
+                        .mimetype("application/vnd.iccprofile")
+                        .glob("*.icc")
+                        .glob("*.icm");
+                    match portal::open_file(&fl!("choose-display-profile"), filter).await {
+                        Some(path) => Message::SetDisplayProfile(Some(path)),
+                        None => Message::None,
                     }
+                });
+            }
+            Message::SetDisplayProfile(path) => {
+                // Choosing a profile turns color management on
+                self.settings.color_managed |= path.is_some();
+                self.settings.display_profile = path;
+                self.save_settings();
+                if self.settings.color_managed {
+                    return self.load_display_profile();
                 }
-
-                if modifiers.control() && key == Key::Character("c".into()) {
-                    return self.copy_to_clipboard(self.last_edited);
+                self.set_display_profile(None);
+            }
+            Message::ColordProfile(path) => {
+                // The user chose a profile or turned color management off while colord was asked
+                if self.settings.color_managed && self.settings.display_profile.is_none() {
+                    self.set_display_profile(path.as_deref());
                 }
             }
-        }
 
-        Task::none()
-    }
+            Message::RecordKeybind(index) => {
+                self.recording = Some(index);
+                self.keybind_error = None;
+            }
+            Message::ResetKeybind(index) => {
+                self.keybind_overrides.remove(keybinds::BINDABLE[index].0);
+                self.save_keybinds();
+            }
+            Message::ResetAllKeybinds => {
+                self.keybind_overrides.clear();
+                self.save_keybinds();
+            }
 
-    fn view(&self) -> Element<'_, Self::Message> {
-        let mut contents = widget::row::with_capacity(self.spaces.len());
+            Message::SearchNamed(query) => {
+                self.named_results = named::search(&query);
+                self.named_query = query;
+            }
+            Message::AddNamed(index) => {
+                if let Some(color) = self.named_results.get(index) {
+                    let column = self.new_column(color.rgb);
+                    self.checkpoint(None);
+                    self.columns.push(column);
+                }
+            }
 
-        for (colorspace, index) in self.spaces.iter().zip(0..) {
-            let (rgb, content, combo_selection) = match colorspace {
-                ColorSpace::Rgb(rgb) => (
-                    rgb.to_rgb(),
-                    rgb.view(self.show_graphs),
-                    0, //ColorSpaceCombo::Rgb,
-                ),
-                ColorSpace::Hsv(hsv) => (
-                    hsv.to_rgb(),
-                    hsv.view(self.show_graphs),
+            Message::RestoreHistoryEntry(index) => {
+                if let Some(entry) = self.pick_history.entries.get(index) {
+                    let space = ColorSpace::from_combo(entry.space, entry.rgb, &self.conversion);
+                    self.checkpoint(None);
+                    self.columns.push(Column::new(space));
+                }
+            }
+            Message::RemoveHistoryEntry(index) => {
+                self.pick_history.remove(index);
+                self.save_pick_history();
+            }
+            Message::ClearHistory => {
+                self.pick_history.clear();
+                self.save_pick_history();
+            }
+
+            Message::SetSampleMode(mode) => {
+                self.sample_mode = mode;
+            }
+            Message::SetSampleSize(size) => {
+                self.sample_size = size;
+            }
+            Message::ToggleContextPage(page) => {
+                if self.context_page == page {
+                    self.core.window.show_context = !self.core.window.show_context;
+                } else {
+                    self.context_page = page;
+                    self.core.window.show_context = true;
+                }
+            }
+            Message::LaunchUrl(url) => match open::that_detached(&url) {
+                Ok(()) => {}
+                Err(e) => {
+                    log::warn!("Failed to open {:?}: {}", url, e);
+                }
+            },
+
+            Message::Dbus(dbus::Event::Ready(publisher)) => {
+                self.dbus = Some(publisher);
+                self.published.clear();
+            }
+            Message::Dbus(dbus::Event::Open(colors)) => {
+                if !colors.is_empty() {
+                    self.checkpoint(None);
+                    let columns: Vec<Column> =
+                        colors.into_iter().map(|rgb| self.new_column(rgb)).collect();
+                    self.columns.extend(columns);
+                    self.last_edited = self.columns.len() - 1;
+                }
+            }
+
+            Message::CopyToClipboard(index) => {
+                return self.copy_to_clipboard(index, CopyFormat::Current);
+            }
+            Message::CopyActive(format) => {
+                return self.copy_to_clipboard(self.last_edited, format);
+            }
+            Message::PickScreenRequest(index) => {
+                let Some(id) = self.columns.get(index).map(|column| column.id) else {
+                    return Task::none();
+                };
+                self.last_edited = index;
+                if self.sample_mode != SampleMode::Pixel {
+                    let (mode, size) = (self.sample_mode, self.sample_size);
+                    return cosmic::task::future(async move {
+                        match sampling::pick_area(mode, size).await {
+                            Some(sample) => Message::SampleScreenResponse(id, sample),
+                            None => Message::None,
+                        }
+                    });
+                }
+
+                return cosmic::task::future(async move {
+                    match portal::pick_color().await {
+                        Some(rgb) => Message::PickScreenResponse((id, rgb)),
+                        None => Message::None,
+                    }
+                });
+            }
+            Message::PickActiveScreen => {
+                if self.last_edited < self.columns.len() {
+                    return self.update(Message::PickScreenRequest(self.last_edited));
+                }
+            }
+            Message::PickScreenResponse((id, rgb)) => {
+                let Some(index) = self.column_index(id).filter(|i| !self.is_locked(*i)) else {
+                    return Task::none();
+                };
+                self.checkpoint(None);
+                self.columns[index].space.convert_from_rgb(rgb);
+                self.sync_linked(index);
+                let text = self.columns[index].space.copy_to_clipboard();
+                self.record_pick(EntryKind::Picked, index, CopyFormat::Current, text);
+            }
+            Message::SampleScreenResponse(id, sample) => {
+                let Some(index) = self.column_index(id).filter(|i| !self.is_locked(*i)) else {
+                    return Task::none();
+                };
+                let (width, height, rgba) = sample.loupe;
+
+                self.checkpoint(None);
+                self.columns[index].space.convert_from_rgb(sample.rgb);
+                self.sync_linked(index);
+                self.loupe = Some((index, widget::image::Handle::from_rgba(width, height, rgba)));
+                let text = self.columns[index].space.copy_to_clipboard();
+                self.record_pick(EntryKind::Picked, index, CopyFormat::Current, text);
+            }
+            Message::Key(key, modifiers) if self.recording.is_some() => {
+                self.record_keybind(&key, modifiers);
+            }
+            Message::Key(key, modifiers) => {
+                for (key_bind, action) in &self.keybinds {
+                    if key_bind.matches(modifiers, &key) {
+                        return self.update(action.message());
+                    }
+                }
+
+                // Move between channels and columns
+                if key == Key::Named(Named::Tab) {
+                    return if modifiers.shift() {
+                        widget_operation::focus_previous()
+                    } else {
+                        widget_operation::focus_next()
+                    };
+                }
+            }
+        }
+
+        self.publish_colors();
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        if self.settings.compact {
+            return self.compact_view();
+        }
+
+        let ctx = self.context();
+        let mut contents = widget::row::with_capacity(self.columns.len());
+
+        for (column, index) in self.columns.iter().zip(0..) {
+            let (locked, linked) = (column.locked, column.linked);
+            let (rgb, content, combo_selection) = match &column.space {
+                ColorSpace::Rgb(rgb) => (
+                    rgb.to_rgb(),
+                    rgb.view(&ctx, self.show_graphs),
+                    0, //ColorSpaceCombo::Rgb,
+                ),
+                ColorSpace::Hsv(hsv) => (
+                    hsv.to_rgb(),
+                    hsv.view(&ctx, self.show_graphs),
                     1, //ColorSpaceCombo::Hsv,
                 ),
                 ColorSpace::Oklab(oklab) => (
                     oklab.to_rgb(),
-                    oklab.view(self.show_graphs),
+                    oklab.view(&ctx, self.show_graphs),
                     2, //ColorSpaceCombo::Oklab,
                 ),
                 ColorSpace::Oklch(oklch) => (
                     oklch.to_rgb(),
-                    oklch.view(self.show_graphs),
+                    oklch.view(&ctx, self.show_graphs),
                     3, //ColorSpaceCombo::Oklch,
                 ),
                 ColorSpace::Cmyk(cmyk) => (
                     cmyk.to_rgb(),
-                    cmyk.view(self.show_graphs),
+                    cmyk.view(&ctx, self.show_graphs),
                     4, //ColorSpaceCombo::Cmyk,
                 ),
+                ColorSpace::Hdr(hdr) => (
+                    hdr.to_rgb(),
+                    hdr.view(&ctx, self.show_graphs),
+                    5, //ColorSpaceCombo::Hdr,
+                ),
             };
 
             let min_rgb = rgb[0].min(rgb[1]).min(rgb[2]).min(0.0);
@@ -322,25 +1536,100 @@ impl Application for ColorPicker {
                 (rgb[1] - min_rgb) / max_rgb,
                 (rgb[2] - min_rgb) / max_rgb,
             ];
+            // Locked HDR columns keep the SDR white they were stored with
+            let sdr_white = column
+                .space
+                .sdr_white()
+                .unwrap_or(self.conversion.sdr_white);
+
+            let mut space_row = widget::row::with_capacity(3)
+                .push(
+                    widget::dropdown(&self.colorspace_names, Some(combo_selection), move |t| {
+                        Message::ChangeColorSpace { index, selected: t }
+                    })
+                    .width(Length::Fill),
+                )
+                .align_y(Vertical::Center)
+                .spacing(10.0);
+            if let Some(unit) = column.space.unit() {
+                // The first entry follows the global unit
+                let selected = match unit {
+                    Some(unit) => Unit::ALL.iter().position(|u| *u == unit).map(|i| i + 1),
+                    None => Some(0),
+                };
+                space_row = space_row.push(widget::dropdown(
+                    &self.column_unit_names,
+                    selected,
+                    move |selected| Message::ChangeUnit { index, selected },
+                ));
+            }
+            if let Some(encoding) = column.space.encoding() {
+                let selected = Encoding::ALL.iter().position(|e| *e == encoding);
+                space_row = space_row.push(widget::dropdown(
+                    &self.encoding_names,
+                    selected,
+                    move |selected| Message::ChangeEncoding { index, selected },
+                ));
+            }
+            space_row = space_row.push(
+                widget::button::icon(widget::icon::from_name("view-more-symbolic"))
+                    .selected(self.operations == Some(index))
+                    .on_press(Message::ToggleOperations(index))
+                    .tooltip(fl!("adjust")),
+            );
+
+            // The second swatch shows how the color reproduces on the proof target
+            let proof_target = self
+                .settings
+                .soft_proof
+                .then_some(self.settings.proof_target);
+            let proof_block = match proof_target {
+                Some(target) => {
+                    let proofed = proof::apply(target, rgb, &self.conversion);
+                    color_block(
+                        Color::from_rgb(proofed[0], proofed[1], proofed[2]),
+                        self.display(),
+                    )
+                    .tooltip(fl!("soft-proof-of", target = target.to_string()))
+                }
+                // HDR colors are compressed into the SDR range instead of normalized
+                None if self.settings.hdr => {
+                    let mapped = colorspace::tone_map(rgb, sdr_white, self.settings.hdr_peak);
+                    color_block(
+                        Color::from_rgb(mapped[0], mapped[1], mapped[2]),
+                        self.display(),
+                    )
+                    .tooltip(fl!("tone-mapped", peak = self.settings.hdr_peak))
+                }
+                None => color_block(
+                    Color::from_rgb(norm_rgb[0], norm_rgb[1], norm_rgb[2]),
+                    self.display(),
+                )
+                .tooltip("Normalized"),
+            };
 
             let mut sidebar = widget::column::with_capacity(3)
                 .push(
                     widget::row::with_capacity(2)
                         .push(
-                            color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]))
+                            color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]), self.display())
                                 .border([true, false, false, true])
                                 .height(100.0)
                                 .tooltip("Linear"),
                         )
-                        .push(
-                            color_block(Color::from_rgb(norm_rgb[0], norm_rgb[1], norm_rgb[2]))
-                                .border([false, true, true, false])
-                                .height(100.0)
-                                .tooltip("Normalized"),
-                        ),
+                        .push(proof_block.border([false, true, true, false]).height(100.0)),
                 )
                 .push(
-                    widget::row::with_capacity(3)
+                    widget::row::with_capacity(8)
+                        .push(
+                            mouse_area(
+                                widget::icon::from_name("list-drag-handle-symbolic")
+                                    .size(16)
+                                    .icon(),
+                            )
+                            .on_press(Message::DragColumn(index))
+                            .interaction(mouse::Interaction::Grab),
+                        )
                         .push(
                             widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
                                 .on_press(Message::CopyToClipboard(index))
@@ -348,27 +1637,100 @@ impl Application for ColorPicker {
                         )
                         .push(
                             widget::button::icon(widget::icon::from_name("edit-find-symbolic"))
-                                .on_press(Message::PickScreenRequest(index))
+                                .on_press_maybe(
+                                    (!locked).then_some(Message::PickScreenRequest(index)),
+                                )
                                 .tooltip("Pick a color from the screen"),
                         )
+                        .push(
+                            widget::button::icon(widget::icon::from_name("tab-new-symbolic"))
+                                .on_press(Message::DuplicateSpace(index))
+                                .tooltip(fl!("duplicate")),
+                        )
+                        .push(
+                            widget::button::icon(widget::icon::from_name(if locked {
+                                "changes-prevent-symbolic"
+                            } else {
+                                "changes-allow-symbolic"
+                            }))
+                            .selected(locked)
+                            .on_press(Message::ToggleLock(index))
+                            .tooltip(if locked {
+                                fl!("unlock")
+                            } else {
+                                fl!("lock")
+                            }),
+                        )
+                        .push(
+                            widget::button::icon(widget::icon::from_name("insert-link-symbolic"))
+                                .selected(linked)
+                                .on_press(Message::ToggleLink(index))
+                                .tooltip(if linked { fl!("unlink") } else { fl!("link") }),
+                        )
                         .push(widget::Space::new().width(Length::Fill))
                         .push(
                             widget::button::icon(widget::icon::from_name(
                                 "user-trash-full-symbolic",
                             ))
-                            .on_press(Message::RemoveSpace(index))
+                            .on_press_maybe((!locked).then_some(Message::RemoveSpace(index)))
                             .class(theme::Button::Destructive)
                             .tooltip("Delete"),
-                        ),
-                )
-                .push(
-                    widget::dropdown(&self.colorspace_names, Some(combo_selection), move |t| {
-                        Message::ChangeColorSpace { index, selected: t }
-                    })
-                    .width(Length::Fill),
+                        )
+                        .align_y(Vertical::Center),
                 )
+                .push(space_row)
                 .spacing(10.0);
 
+            if self.settings.hdr || column.space.encoding().is_some() {
+                sidebar = sidebar.push(widget::text::caption(fl!(
+                    "luminance",
+                    nits = format!("{:.1}", colorspace::luminance(rgb, sdr_white))
+                )));
+            }
+
+            if let Some((color, delta)) = named::nearest(rgb) {
+                sidebar = sidebar.push(widget::tooltip(
+                    widget::text::caption(fl!(
+                        "nearest-named",
+                        name = color.name,
+                        collection = color.collection.to_string()
+                    )),
+                    widget::container(widget::text(fl!(
+                        "delta-e",
+                        delta = format!("{:.1}", delta * 100.0)
+                    ))),
+                    widget::tooltip::Position::Bottom,
+                ));
+            }
+
+            if let Some(target) =
+                proof_target.filter(|target| !proof::in_gamut(*target, rgb, &self.conversion))
+            {
+                sidebar = sidebar.push(
+                    widget::text::caption(fl!("out-of-gamut", target = target.to_string()))
+                        .class(theme::Text::Accent),
+                );
+            }
+
+            if let Some(snap) = self.snap_view(index, rgb, locked) {
+                sidebar = sidebar.push(snap);
+            }
+
+            if self.operations == Some(index) {
+                sidebar = sidebar.push(self.operations_view(index));
+            }
+
+            if let Some((_, handle)) = self.loupe.as_ref().filter(|(i, _)| *i == index) {
+                sidebar = sidebar.push(widget::tooltip(
+                    widget::image(handle.clone())
+                        .filter_method(FilterMethod::Nearest)
+                        .width(Length::Fill)
+                        .height(100.0),
+                    widget::container(widget::text(fl!("sampled-area"))),
+                    widget::tooltip::Position::FollowCursor,
+                ));
+            }
+
             if self.expanded {
                 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
                 let srgb = [
@@ -376,7 +1738,10 @@ impl Application for ColorPicker {
                     (norm_rgb[1] * 255.0) as u8,
                     (norm_rgb[2] * 255.0) as u8,
                 ];
-                let srgb_text = format!("{}, {}, {}", srgb[0], srgb[1], srgb[2]);
+                let srgb_text = match self.srgb_edit.as_ref().filter(|(space, _)| *space == index) {
+                    Some((_, srgb)) => srgb.clone(),
+                    None => format!("{}, {}, {}", srgb[0], srgb[1], srgb[2]),
+                };
                 let hex_text = if self
                     .hex_edit
                     .as_ref()
@@ -390,7 +1755,8 @@ impl Application for ColorPicker {
                 let col = widget::ListColumn::new()
                     .add(
                         widget::text_input("0, 0, 0", srgb_text)
-                            .on_input(|_| Message::None)
+                            .on_input(move |srgb| Message::EditSrgb { space: index, srgb })
+                            .on_submit(move |srgb| Message::SubmitSrgb { space: index, srgb })
                             .label("sRGB"),
                     )
                     .add(
@@ -428,7 +1794,10 @@ impl Application for ColorPicker {
                     .into()
             };
 
-            contents = contents.push(widget::container(elem.apply(widget::scrollable)));
+            contents = contents.push(
+                mouse_area(widget::container(elem.apply(widget::scrollable)))
+                    .on_enter(Message::DragOver(index)),
+            );
         }
 
         {
@@ -456,36 +1825,1230 @@ impl Application for ColorPicker {
             return None;
         }
 
-        Some(Self::about())
+        Some(match self.context_page {
+            ContextPage::About => Self::about(),
+            ContextPage::PickHistory => self.pick_history_page(),
+            ContextPage::Palette => self.palette_page(),
+            ContextPage::NamedColors => self.named_colors_page(),
+            ContextPage::Settings => self.settings_page(),
+            ContextPage::Keybinds => self.keybinds_page(),
+            ContextPage::ExportSheet => self.export_sheet_page(),
+            ContextPage::DesktopTheme => self.desktop_theme_page(),
+        })
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::batch(vec![event::listen_with(
-            |event, status, _windowid| match event {
+        Subscription::batch(vec![
+            event::listen_with(|event, status, _windowid| match event {
                 Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => match status {
                     event::Status::Ignored => Some(Message::Key(key, modifiers)),
                     event::Status::Captured => None,
                 },
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::DropColumn)
+                }
+                Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
                 _ => None,
-            },
-        )])
+            }),
+            dbus::subscription().map(Message::Dbus),
+        ])
+    }
+
+    /// Another launch of the app, whose colors arrive as the action
+    fn dbus_activation(&mut self, msg: dbus_activation::Message) -> Task<Self::Message> {
+        let colors = match msg.msg {
+            dbus_activation::Details::ActivateAction { action, .. } => action
+                .split_whitespace()
+                .filter_map(dbus::parse_color)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        self.update(Message::Dbus(dbus::Event::Open(colors)))
     }
 }
 
 impl ColorPicker {
-    fn copy_to_clipboard(&self, index: usize) -> Task<Message> {
-        let contents = match &self.spaces[index] {
-            ColorSpace::Rgb(rgb) => rgb.copy_to_clipboard(),
-            ColorSpace::Hsv(hsv) => hsv.copy_to_clipboard(),
-            ColorSpace::Oklab(oklab) => oklab.copy_to_clipboard(),
-            ColorSpace::Oklch(oklch) => oklch.copy_to_clipboard(),
-            ColorSpace::Cmyk(cmyk) => cmyk.copy_to_clipboard(),
+    /// Applies the result of an operation on column `index`,
+    /// either in place or as a new column
+    fn apply_rgb(&mut self, index: usize, rgb: [f32; 3]) {
+        if self.operation_target == 1 {
+            self.checkpoint(None);
+            let space =
+                ColorSpace::from_combo(self.columns[index].space.combo(), rgb, &self.conversion);
+            self.columns.insert(index + 1, Column::new(space));
+            self.hex_edit = None;
+            self.srgb_edit = None;
+            self.loupe = None;
+        } else if !self.is_locked(index) {
+            self.checkpoint(None);
+            self.columns[index].space.sync_from_rgb(rgb);
+            self.sync_linked(index);
+        }
+    }
+
+    fn operations_view(&self, index: usize) -> Element<'_, Message> {
+        let mut operations = widget::column::with_capacity(Operation::ALL.len() / 2 + 6);
+        for pair in Operation::ALL.chunks(2) {
+            let mut row = widget::row::with_capacity(2).spacing(10.0);
+            for operation in pair {
+                row = row.push(
+                    widget::button::standard(operation.to_string())
+                        .on_press(Message::Operate(index, *operation))
+                        .width(Length::Fill),
+                );
+            }
+            operations = operations.push(row);
+        }
+
+        let targets = &self.column_names[..self.columns.len().min(MIX_TARGETS)];
+        let mix_with = self.mix_with.and_then(|id| self.column_index(id));
+        operations = operations
+            .push(widget::divider::horizontal::default())
+            .push(widget::text::heading(fl!("mix")))
+            .push(
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::dropdown(
+                            targets,
+                            mix_with.filter(|i| *i < targets.len()),
+                            Message::SetMixWith,
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        widget::dropdown(
+                            &self.colorspace_names,
+                            Some(self.mix_space),
+                            Message::SetMixSpace,
+                        )
+                        .width(Length::Fill),
+                    )
+                    .spacing(10.0),
+            )
+            .push(
+                widget::row::with_capacity(2)
+                    .push(widget::slider(
+                        0.0..=100.0,
+                        self.mix_amount,
+                        Message::SetMixAmount,
+                    ))
+                    .push(widget::text(format!("{:.0}%", self.mix_amount)))
+                    .align_y(Vertical::Center)
+                    .spacing(10.0),
+            )
+            .push(
+                widget::button::standard(fl!("mix"))
+                    .on_press_maybe(mix_with.map(|_| Message::Mix(index)))
+                    .width(Length::Fill),
+            )
+            .push(widget::divider::horizontal::default())
+            .push(
+                widget::dropdown(
+                    &self.operation_targets,
+                    Some(self.operation_target),
+                    Message::SetOperationTarget,
+                )
+                .width(Length::Fill),
+            )
+            .spacing(10.0);
+
+        widget::container(operations)
+            .class(theme::Container::Card)
+            .padding(10.0)
+            .into()
+    }
+
+    /// Whether column `index` ignores edits, a column that no longer exists does too
+    fn is_locked(&self, index: usize) -> bool {
+        self.columns.get(index).is_none_or(|column| column.locked)
+    }
+
+    /// Index of the column with `id`, if it still exists
+    fn column_index(&self, id: u64) -> Option<usize> {
+        self.columns.iter().position(|column| column.id == id)
+    }
+
+    /// Copies the color of column `index` to the other linked columns
+    fn sync_linked(&mut self, index: usize) {
+        if !self.columns[index].linked {
+            return;
+        }
+
+        let rgb = self.columns[index].space.get_rgb();
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            if i != index && column.linked && !column.locked {
+                column.space.sync_from_rgb(rgb);
+            }
+        }
+    }
+
+    /// Saves the current columns so the next edit can be undone
+    fn checkpoint(&mut self, edit: Option<Edit>) {
+        self.history.record(&self.columns, edit);
+    }
+
+    fn copy_to_clipboard(&mut self, index: usize, format: CopyFormat) -> Task<Message> {
+        let Some(Column { space, .. }) = self.columns.get(index) else {
+            return Task::none();
         };
+        // The copy button and shortcut follow the user's default format
+        let format = match format {
+            CopyFormat::Current => self.settings.copy_format,
+            format => format,
+        };
+        let contents = format.format(space, &self.conversion);
 
         info!("Copying \"{}\" to clipboard", contents);
+        self.record_pick(EntryKind::Copied, index, format, contents.clone());
         clipboard::write(contents)
     }
 
+    fn tools_items(&self) -> Vec<menu::Item<Action, String>> {
+        let mut sizes: Vec<_> = palette::SIZES
+            .into_iter()
+            .map(|size| {
+                menu::Item::CheckBox(
+                    fl!("palette-size", size = size),
+                    None,
+                    self.palette_size == size,
+                    Action::PaletteSize(size),
+                )
+            })
+            .collect();
+        sizes.insert(0, menu::Item::Divider);
+
+        let mut items = vec![
+            menu::Item::Button(fl!("named-colors"), None, Action::NamedColors),
+            menu::Item::Button(fl!("export-sheet"), None, Action::ExportSheet),
+            menu::Item::Divider,
+            menu::Item::Button(fl!("desktop-theme"), None, Action::DesktopTheme),
+            menu::Item::Button(fl!("read-desktop-theme"), None, Action::ReadDesktopTheme),
+            menu::Item::Button(fl!("set-accent-color"), None, Action::SetAccentColor),
+            menu::Item::Divider,
+            menu::Item::Button(
+                fl!("extract-palette-file"),
+                None,
+                Action::ExtractPalette(palette::Source::File),
+            ),
+            menu::Item::Button(
+                fl!("extract-palette-screenshot"),
+                None,
+                Action::ExtractPalette(palette::Source::Screenshot),
+            ),
+        ];
+        items.extend(sizes);
+        items.push(menu::Item::Divider);
+        items.push(menu::Item::Folder(fl!("snap-palette"), self.snap_items()));
+        items
+    }
+
+    fn snap_items(&self) -> Vec<menu::Item<Action, String>> {
+        let mut items = vec![menu::Item::CheckBox(
+            fl!("snap-off"),
+            None,
+            self.settings.snap_palette.is_none(),
+            Action::SnapPalette(None),
+        )];
+        items.extend(
+            snap::available(self.imported.as_ref())
+                .into_iter()
+                .map(|palette| {
+                    menu::Item::CheckBox(
+                        palette.name(self.imported.as_ref()),
+                        None,
+                        self.settings.snap_palette == Some(palette),
+                        Action::SnapPalette(Some(palette)),
+                    )
+                }),
+        );
+        items.push(menu::Item::Divider);
+        items.push(menu::Item::Button(
+            fl!("import-palette"),
+            None,
+            Action::ImportPalette,
+        ));
+
+        items
+    }
+
+    /// Nearest entry of the snap palette with buttons to copy and apply it
+    fn snap_view(&self, index: usize, rgb: [f32; 3], locked: bool) -> Option<Element<'_, Message>> {
+        let palette = self.settings.snap_palette?;
+        let (entry, delta) = snap::nearest(palette, rgb, self.imported.as_ref())?;
+
+        let mut text = widget::column::with_capacity(2).push(widget::text::caption(fl!(
+            "snap-match",
+            name = entry.name,
+            palette = palette.name(self.imported.as_ref()),
+            delta = format!("{:.1}", delta * 100.0)
+        )));
+        if let Some(ansi) = entry.ansi {
+            text = text.push(widget::text::caption(fl!("snap-ansi", code = ansi)));
+        }
+
+        Some(
+            widget::row::with_capacity(4)
+                .push(
+                    color_block(
+                        Color::from_rgb(entry.rgb[0], entry.rgb[1], entry.rgb[2]),
+                        self.display(),
+                    )
+                    .width(24.0)
+                    .height(24.0),
+                )
+                .push(text.width(Length::Fill))
+                .push(
+                    widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
+                        .on_press(Message::CopySnap(index))
+                        .tooltip(fl!("snap-copy")),
+                )
+                .push(
+                    widget::button::icon(widget::icon::from_name("object-select-symbolic"))
+                        .on_press_maybe((!locked).then_some(Message::SnapToPalette(index)))
+                        .tooltip(fl!("snap-apply")),
+                )
+                .align_y(Vertical::Center)
+                .spacing(10.0)
+                .into(),
+        )
+    }
+
+    fn proof_items(&self) -> Vec<menu::Item<Action, String>> {
+        proof::Target::ALL
+            .into_iter()
+            .map(|target| {
+                menu::Item::CheckBox(
+                    target.to_string(),
+                    None,
+                    self.settings.proof_target == target,
+                    Action::ProofTarget(target),
+                )
+            })
+            .collect()
+    }
+
+    fn sampling_items(&self) -> Vec<menu::Item<Action, String>> {
+        let mut items: Vec<_> = SampleMode::ALL
+            .into_iter()
+            .map(|mode| {
+                menu::Item::CheckBox(
+                    mode.to_string(),
+                    None,
+                    self.sample_mode == mode,
+                    Action::SampleMode(mode),
+                )
+            })
+            .collect();
+
+        items.push(menu::Item::Divider);
+        items.extend(sampling::SIZES.into_iter().map(|size| {
+            menu::Item::CheckBox(
+                fl!("sample-size", size = size),
+                None,
+                self.sample_size == size,
+                Action::SampleSize(size),
+            )
+        }));
+
+        items
+    }
+
+    /// Records the color of column `index` in the pick history as `text` in `format`
+    fn record_pick(&mut self, kind: EntryKind, index: usize, format: CopyFormat, text: String) {
+        let space = &self.columns[index].space;
+        self.pick_history.push(Entry::new(
+            kind,
+            space.get_rgb(),
+            space.combo(),
+            format,
+            text,
+        ));
+        self.save_pick_history();
+    }
+
+    fn save_pick_history(&self) {
+        if let Some(state) = &self.state {
+            self.pick_history.save(state);
+        }
+    }
+
+    fn palette_page(&self) -> ContextDrawer<'_, Message> {
+        let mut list = widget::ListColumn::new();
+        for (swatch, index) in self.palette.iter().zip(0..) {
+            let rgb = swatch.rgb;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let srgb = [
+                (rgb[0].clamp(0.0, 1.0) * 255.0).round() as u8,
+                (rgb[1].clamp(0.0, 1.0) * 255.0).round() as u8,
+                (rgb[2].clamp(0.0, 1.0) * 255.0).round() as u8,
+            ];
+
+            list = list.add(
+                widget::row::with_capacity(3)
+                    .push(
+                        color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]), self.display())
+                            .width(40.0)
+                            .height(40.0),
+                    )
+                    .push(
+                        widget::column::with_capacity(2)
+                            .push(widget::text::body(format!("#{}", hex::encode(srgb))))
+                            .push(widget::text::caption(fl!(
+                                "palette-coverage",
+                                percent = format!("{:.1}", swatch.coverage * 100.0)
+                            )))
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("list-add-symbolic"))
+                            .on_press(Message::AddSwatch(index))
+                            .tooltip(fl!("palette-add")),
+                    )
+                    .align_y(Vertical::Center)
+                    .spacing(10.0),
+            );
+        }
+
+        ContextDrawer {
+            title: Some(fl!("palette").into()),
+            actions: None,
+            header: None,
+            content: list.into(),
+            footer: Some(
+                widget::button::suggested(fl!("palette-add-all"))
+                    .on_press(Message::AddAllSwatches)
+                    .into(),
+            ),
+            on_close: Message::ToggleContextPage(ContextPage::Palette),
+        }
+    }
+
+    /// A new column in the default color space
+    fn new_column(&self, rgb: [f32; 3]) -> Column {
+        Column::new(ColorSpace::from_combo(
+            self.settings.default_space,
+            rgb,
+            &self.conversion,
+        ))
+    }
+
+    /// Resizes the window and sets its level for the current mode.
+    /// Only the compact window can stay on top
+    fn window_mode(&self) -> Task<Message> {
+        let Some(id) = self.core.main_window_id() else {
+            return Task::none();
+        };
+
+        let (size, level) = match self.settings.compact {
+            true if self.settings.always_on_top => (COMPACT_SIZE, Level::AlwaysOnTop),
+            true => (COMPACT_SIZE, Level::Normal),
+            false => (self.window_size, Level::Normal),
+        };
+        Task::batch([window::resize(id, size), window::set_level(id, level)])
+    }
+
+    /// Swatch, hex and buttons of the active column
+    fn compact_view(&self) -> Element<'_, Message> {
+        let Some(column) = self.columns.get(self.last_edited) else {
+            return widget::container(
+                widget::button::standard(fl!("add-column")).on_press(Message::AddSpace),
+            )
+            .center(Length::Fill)
+            .into();
+        };
+        let index = self.last_edited;
+        let rgb = column.space.get_rgb();
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let srgb = rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        let hex_text = match self.hex_edit.as_ref().filter(|(space, _)| *space == index) {
+            Some((_, hex)) => hex.clone(),
+            None => format!("#{}", hex::encode(srgb)),
+        };
+
+        let buttons = widget::row::with_capacity(5)
+            .push(
+                widget::button::icon(widget::icon::from_name("edit-find-symbolic"))
+                    .on_press_maybe((!column.locked).then_some(Message::PickScreenRequest(index)))
+                    .tooltip(fl!("pick-screen")),
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
+                    .on_press(Message::CopyToClipboard(index))
+                    .tooltip(fl!("copy")),
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("view-pin-symbolic"))
+                    .selected(self.settings.always_on_top)
+                    .on_press(Message::ToggleAlwaysOnTop)
+                    .tooltip(fl!("always-on-top")),
+            )
+            .push(widget::Space::new().width(Length::Fill))
+            .push(
+                widget::button::icon(widget::icon::from_name("view-fullscreen-symbolic"))
+                    .on_press(Message::ToggleCompact)
+                    .tooltip(fl!("leave-compact")),
+            )
+            .align_y(Vertical::Center);
+
+        widget::column::with_capacity(3)
+            .push(
+                color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]), self.display())
+                    .height(Length::Fill),
+            )
+            .push(
+                widget::text_input("#000000", hex_text)
+                    .on_input(move |hex| Message::EditHex { space: index, hex })
+                    .on_submit(move |hex| Message::SubmitHex { space: index, hex }),
+            )
+            .push(buttons)
+            .spacing(10.0)
+            .padding(10.0)
+            .into()
+    }
+
+    /// Sends the column colors to the D-Bus service when they changed
+    fn publish_colors(&mut self) {
+        let Some(dbus) = &self.dbus else {
+            return;
+        };
+
+        let colors: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| CopyFormat::Hex.format(&column.space, &self.conversion))
+            .collect();
+        if colors != self.published {
+            dbus.publish(colors.clone());
+            self.published = colors;
+        }
+    }
+
+    /// Colors of the swatch sheet's source
+    fn sheet_colors(&self) -> Vec<[f32; 3]> {
+        match self.sheet_source {
+            0 => self.columns.iter().map(|c| c.space.get_rgb()).collect(),
+            1 => self
+                .columns
+                .get(self.last_edited)
+                .map(|c| c.space.get_rgb())
+                .into_iter()
+                .collect(),
+            _ => self.palette.iter().map(|swatch| swatch.rgb).collect(),
+        }
+    }
+
+    /// Colors of the roles mapped to a column
+    fn theme_colors(&self) -> Vec<(Role, [f32; 3])> {
+        Role::ALL
+            .into_iter()
+            .zip(self.theme_roles)
+            .filter_map(|(role, id)| {
+                let column = &self.columns[self.column_index(id?)?];
+                Some((role, column.space.get_rgb()))
+            })
+            .collect()
+    }
+
+    fn desktop_theme_page(&self) -> ContextDrawer<'_, Message> {
+        let sources = &self.theme_sources[..=self.columns.len().min(ROLE_SOURCES)];
+
+        let mut roles = widget::settings::section().title(fl!("theme-roles"));
+        for (i, role) in Role::ALL.into_iter().enumerate() {
+            let index = self.theme_roles[i].and_then(|id| self.column_index(id));
+            // The first source keeps COSMIC's default
+            let selected = index.map_or(0, |index| index + 1);
+            let mut item = widget::row::with_capacity(2)
+                .align_y(Vertical::Center)
+                .spacing(10.0);
+            if let Some(index) = index {
+                let rgb = self.columns[index].space.get_rgb();
+                item = item.push(
+                    color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]), self.display())
+                        .width(24.0)
+                        .height(24.0),
+                );
+            }
+            item = item.push(widget::dropdown(
+                sources,
+                (selected < sources.len()).then_some(selected),
+                move |selected| Message::SetThemeRole(i, selected),
+            ));
+            roles = roles.add(widget::settings::item(role.to_string(), item));
+        }
+
+        let content = widget::column::with_capacity(3)
+            .push(
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::button::standard(fl!("read-desktop-theme"))
+                            .on_press(Message::ReadDesktopTheme),
+                    )
+                    .push(
+                        widget::button::standard(fl!("set-accent-color")).on_press_maybe(
+                            (self.last_edited < self.columns.len())
+                                .then_some(Message::SetAccentColor),
+                        ),
+                    )
+                    .spacing(10.0),
+            )
+            .push(widget::settings::section().add(widget::settings::item(
+                fl!("theme-dark"),
+                widget::toggler(self.theme_dark).on_toggle(Message::SetThemeDark),
+            )))
+            .push(roles)
+            .spacing(24.0);
+
+        ContextDrawer {
+            title: Some(fl!("desktop-theme-title").into()),
+            actions: None,
+            header: None,
+            content: content.into(),
+            footer: Some(
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::button::standard(fl!("apply-desktop-theme"))
+                            .on_press(Message::ApplyDesktopTheme),
+                    )
+                    .push(
+                        widget::button::suggested(fl!("export-theme"))
+                            .on_press(Message::ExportTheme),
+                    )
+                    .spacing(10.0)
+                    .into(),
+            ),
+            on_close: Message::ToggleContextPage(ContextPage::DesktopTheme),
+        }
+    }
+
+    fn export_sheet_page(&self) -> ContextDrawer<'_, Message> {
+        let size = sheet::SIZES.iter().position(|s| *s == self.sheet.size);
+        let per_row = sheet::PER_ROW.iter().position(|p| *p == self.sheet.per_row);
+        let background = sheet::Background::ALL
+            .iter()
+            .position(|b| *b == self.sheet.background);
+        let colors = self.sheet_colors();
+
+        let mut preview = widget::row::with_capacity(colors.len()).spacing(4.0);
+        for rgb in &colors {
+            preview = preview.push(
+                color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]), self.display())
+                    .width(24.0)
+                    .height(24.0),
+            );
+        }
+
+        let mut labels = widget::settings::section().title(fl!("sheet-labels"));
+        for label in sheet::Label::ALL {
+            labels = labels.add(widget::settings::item(
+                label.to_string(),
+                widget::toggler(self.sheet.labels.contains(&label))
+                    .on_toggle(move |shown| Message::ToggleSheetLabel(label, shown)),
+            ));
+        }
+
+        let content = widget::column::with_capacity(3)
+            .push(
+                widget::settings::section()
+                    .add(widget::settings::item(
+                        fl!("sheet-source"),
+                        widget::dropdown(
+                            &self.sheet_sources,
+                            Some(self.sheet_source),
+                            Message::SetSheetSource,
+                        ),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("sheet-size"),
+                        widget::dropdown(&self.sheet_size_names, size, Message::SetSheetSize),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("sheet-per-row"),
+                        widget::dropdown(
+                            &self.sheet_per_row_names,
+                            per_row,
+                            Message::SetSheetPerRow,
+                        ),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("sheet-background"),
+                        widget::dropdown(
+                            &self.sheet_background_names,
+                            background,
+                            Message::SetSheetBackground,
+                        ),
+                    )),
+            )
+            .push(labels)
+            .push(widget::scrollable(preview).direction(Direction::Horizontal(Scrollbar::new())))
+            .spacing(24.0);
+
+        let can_export = !colors.is_empty();
+        ContextDrawer {
+            title: Some(fl!("export-sheet-title").into()),
+            actions: None,
+            header: None,
+            content: content.into(),
+            footer: Some(
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::button::standard(fl!("sheet-export-svg")).on_press_maybe(
+                            can_export.then_some(Message::ExportSheet(sheet::Format::Svg)),
+                        ),
+                    )
+                    .push(
+                        widget::button::suggested(fl!("sheet-export-png")).on_press_maybe(
+                            can_export.then_some(Message::ExportSheet(sheet::Format::Png)),
+                        ),
+                    )
+                    .spacing(10.0)
+                    .into(),
+            ),
+            on_close: Message::ToggleContextPage(ContextPage::ExportSheet),
+        }
+    }
+
+    fn save_settings(&self) {
+        if let Some(config) = &self.config {
+            self.settings.save(config);
+        }
+    }
+
+    /// Loads the CMYK profile of the settings, unlocked CMYK columns keep their color
+    fn load_cmyk_profile(&mut self) {
+        self.checkpoint(None);
+        self.conversion.cmyk_profile = self.settings.load_cmyk_profile();
+
+        for column in self.columns.iter_mut().filter(|column| !column.locked) {
+            if let ColorSpace::Cmyk(cmyk) = &mut column.space {
+                cmyk.set_profile(self.conversion.cmyk_profile.clone());
+            }
+        }
+        // The CMYK gamut depends on the profile
+        self.apply_proof();
+    }
+
+    /// Sets the SDR white of the settings, unlocked HDR columns keep their color
+    fn set_sdr_white(&mut self) {
+        self.checkpoint(None);
+        self.conversion.sdr_white = self.settings.sdr_white;
+
+        for column in self.columns.iter_mut().filter(|column| !column.locked) {
+            if let ColorSpace::Hdr(hdr) = &mut column.space {
+                hdr.set_sdr_white(self.conversion.sdr_white);
+            }
+        }
+    }
+
+    /// Loads the imported palette of the settings
+    fn load_imported_palette(&mut self) {
+        self.imported = self
+            .settings
+            .imported_palette
+            .as_deref()
+            .and_then(snap::Imported::load);
+        if self.imported.is_none() {
+            self.settings.imported_palette = None;
+        }
+    }
+
+    /// Proofs the target of the settings in the graphs
+    fn apply_proof(&mut self) {
+        self.proof = self
+            .settings
+            .soft_proof
+            .then(|| Rc::new(Proof::new(self.settings.proof_target, &self.conversion)));
+    }
+
+    /// Loads the display profile of the settings, or the one colord has for the monitor
+    fn load_display_profile(&mut self) -> Task<Message> {
+        if let Some(path) = self.settings.display_profile.clone() {
+            self.set_display_profile(Some(&path));
+            return Task::none();
+        }
+
+        cosmic::task::future(async move { Message::ColordProfile(display::colord_profile().await) })
+    }
+
+    fn set_display_profile(&mut self, path: Option<&Path>) {
+        self.display_profile = path.and_then(DisplayProfile::load).map(Rc::new);
+    }
+
+    /// Profile that previews are shown through, `None` when unmanaged
+    fn display(&self) -> Option<&DisplayProfile> {
+        self.display_profile
+            .as_deref()
+            .filter(|_| self.settings.color_managed)
+    }
+
+    /// How channels are shown and edited, with the display profile when managed and the soft proof
+    fn context(&self) -> colorspace::Context {
+        colorspace::Context {
+            display: self
+                .display_profile
+                .clone()
+                .filter(|_| self.settings.color_managed),
+            proof: self.proof.clone(),
+            ..self.settings.context()
+        }
+    }
+
+    fn settings_page(&self) -> ContextDrawer<'_, Message> {
+        let default_space = self
+            .colorspace_selections
+            .iter()
+            .position(|space| *space == self.settings.default_space);
+        let precision = settings::PRECISIONS
+            .iter()
+            .position(|precision| *precision == self.settings.precision);
+        let unit = Unit::ALL
+            .iter()
+            .position(|unit| *unit == self.settings.unit);
+        let copy_format = settings::COPY_FORMATS
+            .iter()
+            .position(|format| *format == self.settings.copy_format);
+        let scroll_step = settings::SCROLL_STEPS
+            .iter()
+            .position(|step| (step - self.settings.scroll_step).abs() < f32::EPSILON);
+        let app_theme = AppTheme::ALL
+            .iter()
+            .position(|theme| *theme == self.settings.app_theme);
+        let intent = Intent::ALL
+            .iter()
+            .position(|intent| *intent == self.settings.rendering_intent);
+        let ink_limit = settings::INK_LIMITS
+            .iter()
+            .position(|limit| *limit == self.settings.ink_limit);
+        let sdr_white = settings::SDR_WHITES
+            .iter()
+            .position(|nits| *nits == self.settings.sdr_white);
+        let hdr_peak = settings::HDR_PEAKS
+            .iter()
+            .position(|nits| *nits == self.settings.hdr_peak);
+
+        let mut cmyk_profile = widget::row::with_capacity(3)
+            .push(widget::text::body(
+                self.conversion
+                    .cmyk_profile
+                    .as_ref()
+                    .map_or_else(|| fl!("cmyk-profile-none"), |profile| profile.name.clone()),
+            ))
+            .push(widget::button::standard(fl!("choose")).on_press(Message::ChooseCmykProfile))
+            .align_y(Vertical::Center)
+            .spacing(10.0);
+        if self.conversion.cmyk_profile.is_some() {
+            cmyk_profile = cmyk_profile.push(
+                widget::button::icon(widget::icon::from_name("edit-clear-symbolic"))
+                    .on_press(Message::SetCmykProfile(None)),
+            );
+        }
+
+        let mut imported_palette = widget::row::with_capacity(3)
+            .push(widget::text::body(self.imported.as_ref().map_or_else(
+                || fl!("imported-palette-none"),
+                |imported| imported.name.clone(),
+            )))
+            .push(widget::button::standard(fl!("choose")).on_press(Message::ImportPalette))
+            .align_y(Vertical::Center)
+            .spacing(10.0);
+        if self.imported.is_some() {
+            imported_palette = imported_palette.push(
+                widget::button::icon(widget::icon::from_name("edit-clear-symbolic"))
+                    .on_press(Message::SetImportedPalette(None)),
+            );
+        }
+
+        let mut display_profile = widget::row::with_capacity(3)
+            .push(widget::text::body(
+                self.display_profile.as_ref().map_or_else(
+                    || fl!("display-profile-none"),
+                    |profile| profile.name.clone(),
+                ),
+            ))
+            .push(widget::button::standard(fl!("choose")).on_press(Message::ChooseDisplayProfile))
+            .align_y(Vertical::Center)
+            .spacing(10.0);
+        // Without a chosen profile the one of colord is used
+        if self.settings.display_profile.is_some() {
+            display_profile = display_profile.push(
+                widget::button::icon(widget::icon::from_name("edit-clear-symbolic"))
+                    .on_press(Message::SetDisplayProfile(None)),
+            );
+        }
+
+        let content = widget::column::with_capacity(7)
+            .push(
+                widget::settings::section()
+                    .title(fl!("settings-appearance"))
+                    .add(widget::settings::item(
+                        fl!("settings-theme"),
+                        widget::dropdown(&self.theme_names, app_theme, Message::SetAppTheme),
+                    )),
+            )
+            .push(
+                widget::settings::section()
+                    .title(fl!("settings-colors"))
+                    .add(widget::settings::item(
+                        fl!("settings-default-space"),
+                        widget::dropdown(
+                            &self.colorspace_names,
+                            default_space,
+                            Message::SetDefaultSpace,
+                        ),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-precision"),
+                        widget::dropdown(&self.precision_names, precision, Message::SetPrecision),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-unit"),
+                        widget::dropdown(&self.unit_names, unit, Message::SetUnit),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-copy-format"),
+                        widget::dropdown(
+                            &self.copy_format_names,
+                            copy_format,
+                            Message::SetCopyFormat,
+                        ),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-scroll-step"),
+                        widget::dropdown(
+                            &self.scroll_step_names,
+                            scroll_step,
+                            Message::SetScrollStep,
+                        ),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-imported-palette"),
+                        imported_palette,
+                    )),
+            )
+            .push(
+                widget::settings::section()
+                    .title(fl!("settings-display"))
+                    .add(widget::settings::item(
+                        fl!("color-managed"),
+                        widget::toggler(self.settings.color_managed)
+                            .on_toggle(|_| Message::ToggleColorManaged),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-display-profile"),
+                        display_profile,
+                    )),
+            )
+            .push(
+                widget::settings::section()
+                    .title(fl!("settings-print"))
+                    .add(widget::settings::item(
+                        fl!("settings-cmyk-profile"),
+                        cmyk_profile,
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-rendering-intent"),
+                        widget::dropdown(&self.intent_names, intent, Message::SetRenderingIntent),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-ink-limit"),
+                        widget::dropdown(&self.ink_limit_names, ink_limit, Message::SetInkLimit),
+                    )),
+            )
+            .push(
+                widget::settings::section()
+                    .title(fl!("settings-hdr"))
+                    .add(widget::settings::item(
+                        fl!("hdr-mode"),
+                        widget::toggler(self.settings.hdr).on_toggle(|_| Message::ToggleHdr),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-sdr-white"),
+                        widget::dropdown(&self.sdr_white_names, sdr_white, Message::SetSdrWhite),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("settings-hdr-peak"),
+                        widget::dropdown(&self.hdr_peak_names, hdr_peak, Message::SetHdrPeak),
+                    )),
+            )
+            .push(
+                widget::settings::section()
+                    .title(fl!("settings-startup"))
+                    .add(widget::settings::item(
+                        fl!("graphs"),
+                        widget::toggler(self.settings.show_graphs)
+                            .on_toggle(Message::SetStartupGraphs),
+                    ))
+                    .add(widget::settings::item(
+                        fl!("expanded"),
+                        widget::toggler(self.settings.expanded)
+                            .on_toggle(Message::SetStartupExpanded),
+                    )),
+            )
+            .push(
+                widget::button::standard(fl!("keybinds"))
+                    .on_press(Message::ToggleContextPage(ContextPage::Keybinds)),
+            )
+            .spacing(24.0);
+
+        ContextDrawer {
+            title: Some(fl!("settings").into()),
+            actions: None,
+            header: None,
+            content: content.into(),
+            footer: None,
+            on_close: Message::ToggleContextPage(ContextPage::Settings),
+        }
+    }
+
+    /// Assigns the pressed shortcut to the action being recorded.
+    /// Escape cancels, Backspace unbinds the action
+    fn record_keybind(&mut self, key: &Key, modifiers: Modifiers) {
+        let Some(index) = self.recording else {
+            return;
+        };
+
+        let shortcut = match key {
+            Key::Named(Named::Escape) => {
+                self.recording = None;
+                return;
+            }
+            Key::Named(Named::Backspace) if modifiers.is_empty() => String::new(),
+            _ => match keybinds::from_key(key, modifiers) {
+                Some(shortcut) => shortcut,
+                // Wait for a non-modifier key
+                None => return,
+            },
+        };
+
+        if let Some(other) = keybinds::conflict(&self.keybind_overrides, index, &shortcut) {
+            self.keybind_error = Some(fl!(
+                "keybind-conflict",
+                shortcut = shortcut,
+                action = keybinds::label(other)
+            ));
+            self.recording = None;
+            return;
+        }
+
+        self.keybind_overrides
+            .insert(keybinds::BINDABLE[index].0.to_string(), shortcut);
+        self.recording = None;
+        self.save_keybinds();
+    }
+
+    fn save_keybinds(&mut self) {
+        self.keybind_error = None;
+        self.keybinds = keybinds::build(&self.keybind_overrides);
+        if let Some(config) = &self.config {
+            keybinds::save(config, &self.keybind_overrides);
+        }
+    }
+
+    fn keybinds_page(&self) -> ContextDrawer<'_, Message> {
+        let mut list = widget::ListColumn::new();
+        for index in 0..keybinds::BINDABLE.len() {
+            let shortcut = keybinds::shortcut(&self.keybind_overrides, index);
+            let customized = self
+                .keybind_overrides
+                .contains_key(keybinds::BINDABLE[index].0);
+
+            let mut label = widget::column::with_capacity(2)
+                .push(widget::text::body(keybinds::label(index)))
+                .width(Length::Fill);
+            if let Some(other) = keybinds::conflict(&self.keybind_overrides, index, shortcut) {
+                label = label.push(
+                    widget::text::caption(fl!(
+                        "keybind-conflict",
+                        shortcut = shortcut,
+                        action = keybinds::label(other)
+                    ))
+                    .class(theme::Text::Accent),
+                );
+            }
+
+            let button_label = if self.recording == Some(index) {
+                fl!("keybind-recording")
+            } else if shortcut.is_empty() {
+                fl!("keybind-none")
+            } else {
+                shortcut.to_string()
+            };
+
+            list = list.add(
+                widget::row::with_capacity(3)
+                    .push(label)
+                    .push(
+                        widget::button::standard(button_label)
+                            .selected(self.recording == Some(index))
+                            .on_press(Message::RecordKeybind(index)),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("edit-undo-symbolic"))
+                            .on_press_maybe(customized.then_some(Message::ResetKeybind(index)))
+                            .tooltip(fl!("keybind-reset")),
+                    )
+                    .align_y(Vertical::Center)
+                    .spacing(10.0),
+            );
+        }
+
+        let mut header = widget::column::with_capacity(2)
+            .push(widget::text::caption(fl!("keybind-help")))
+            .spacing(10.0);
+        if let Some(error) = &self.keybind_error {
+            header = header.push(widget::text::body(error.clone()).class(theme::Text::Accent));
+        }
+
+        ContextDrawer {
+            title: Some(fl!("keybinds").into()),
+            actions: None,
+            header: Some(header.into()),
+            content: list.into(),
+            footer: Some(
+                widget::button::destructive(fl!("keybind-reset-all"))
+                    .on_press_maybe(
+                        (!self.keybind_overrides.is_empty()).then_some(Message::ResetAllKeybinds),
+                    )
+                    .into(),
+            ),
+            on_close: Message::ToggleContextPage(ContextPage::Keybinds),
+        }
+    }
+
+    fn named_colors_page(&self) -> ContextDrawer<'_, Message> {
+        let mut list = widget::ListColumn::new();
+        for (color, index) in self.named_results.iter().zip(0..) {
+            let rgb = color.rgb;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let srgb = [
+                (rgb[0] * 255.0).round() as u8,
+                (rgb[1] * 255.0).round() as u8,
+                (rgb[2] * 255.0).round() as u8,
+            ];
+
+            list = list.add(
+                widget::row::with_capacity(3)
+                    .push(
+                        color_block(Color::from_rgb(rgb[0], rgb[1], rgb[2]), self.display())
+                            .width(40.0)
+                            .height(40.0),
+                    )
+                    .push(
+                        widget::column::with_capacity(2)
+                            .push(widget::text::body(color.name))
+                            .push(widget::text::caption(format!(
+                                "{} · #{}",
+                                color.collection,
+                                hex::encode(srgb)
+                            )))
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("list-add-symbolic"))
+                            .on_press(Message::AddNamed(index))
+                            .tooltip(fl!("named-add")),
+                    )
+                    .align_y(Vertical::Center)
+                    .spacing(10.0),
+            );
+        }
+
+        let content: Element<'_, Message> = if self.named_results.is_empty() {
+            widget::text::body(fl!("named-empty")).into()
+        } else {
+            list.into()
+        };
+
+        ContextDrawer {
+            title: Some(fl!("named-colors").into()),
+            actions: None,
+            header: Some(
+                widget::search_input(fl!("named-search"), &self.named_query)
+                    .on_input(Message::SearchNamed)
+                    .on_clear(Message::SearchNamed(String::new()))
+                    .into(),
+            ),
+            content,
+            footer: None,
+            on_close: Message::ToggleContextPage(ContextPage::NamedColors),
+        }
+    }
+
+    fn pick_history_page(&self) -> ContextDrawer<'_, Message> {
+        let mut list = widget::ListColumn::new();
+        for (entry, index) in self.pick_history.entries.iter().zip(0..) {
+            let kind = match entry.kind {
+                EntryKind::Picked => fl!("history-picked"),
+                EntryKind::Copied => fl!("history-copied"),
+            };
+            let age = entry.age();
+            let time = if age < 60 {
+                fl!("history-now")
+            } else if age < 60 * 60 {
+                fl!("history-minutes", count = age / 60)
+            } else if age < 60 * 60 * 24 {
+                fl!("history-hours", count = age / (60 * 60))
+            } else {
+                fl!("history-days", count = age / (60 * 60 * 24))
+            };
+
+            list = list.add(
+                widget::row::with_capacity(4)
+                    .push(
+                        color_block(
+                            Color::from_rgb(entry.rgb[0], entry.rgb[1], entry.rgb[2]),
+                            self.display(),
+                        )
+                        .width(40.0)
+                        .height(40.0),
+                    )
+                    .push(
+                        widget::column::with_capacity(2)
+                            .push(widget::text::body(entry.text.clone()))
+                            .push(widget::text::caption(match entry.format {
+                                CopyFormat::Current => format!("{kind} · {} · {time}", entry.space),
+                                format => {
+                                    format!("{kind} · {} · {format} · {time}", entry.space)
+                                }
+                            }))
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("list-add-symbolic"))
+                            .on_press(Message::RestoreHistoryEntry(index))
+                            .tooltip(fl!("history-restore")),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                            .on_press(Message::RemoveHistoryEntry(index))
+                            .tooltip(fl!("history-remove")),
+                    )
+                    .align_y(Vertical::Center)
+                    .spacing(10.0),
+            );
+        }
+
+        let content: Element<'_, Message> = if self.pick_history.entries.is_empty() {
+            widget::text::body(fl!("history-empty")).into()
+        } else {
+            list.into()
+        };
+
+        ContextDrawer {
+            title: Some(fl!("pick-history").into()),
+            actions: None,
+            header: None,
+            content,
+            footer: Some(
+                widget::button::destructive(fl!("history-clear"))
+                    .on_press(Message::ClearHistory)
+                    .into(),
+            ),
+            on_close: Message::ToggleContextPage(ContextPage::PickHistory),
+        }
+    }
+
     fn about<'a>() -> ContextDrawer<'a, Message> {
         let repository = "https://github.com/PixelDoted/cosmic-ext-color-picker";
         let hash = env!("VERGEN_GIT_SHA");
@@ -519,7 +3082,7 @@ impl ColorPicker {
             header: None,
             content,
             footer: None,
-            on_close: Message::ToggleAboutPage,
+            on_close: Message::ToggleContextPage(ContextPage::About),
         }
     }
 }
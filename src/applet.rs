@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::app::{Core, Task};
+use cosmic::cosmic_config;
+use cosmic::iced::alignment::Vertical;
+use cosmic::iced::platform_specific::shell::commands::popup::{destroy_popup, get_popup};
+use cosmic::iced::widget::mouse_area;
+use cosmic::iced::window::Id;
+use cosmic::iced::{clipboard, Color, Length, Limits};
+use cosmic::{widget, Application, Element};
+
+use crate::app::{ColorPicker, CONFIG_VERSION, STATE_VERSION};
+use crate::colorspace::{ColorSpace, Conversion};
+use crate::fl;
+use crate::pick_history::{Entry, EntryKind, PickHistory};
+use crate::portal;
+use crate::settings::Settings;
+use crate::widgets::color_block;
+
+/// Number of recent colors shown in the popup
+const RECENT: usize = 8;
+
+const ICON: &str = "color-select-symbolic";
+
+pub fn run() -> cosmic::iced::Result {
+    cosmic::applet::run::<Applet>(())
+}
+
+/// Panel applet, picks a color with one click and shows recent colors in a popup.
+/// Shares the settings and pick history of the app
+pub struct Applet {
+    core: Core,
+    popup: Option<Id>,
+    settings: Settings,
+    /// What conversions depend on, from the settings
+    conversion: Conversion,
+    pick_history: PickHistory,
+    state: Option<cosmic_config::Config>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    None,
+    Pick,
+    PickResponse([f32; 3]),
+    Copy(usize),
+    OpenApp,
+    TogglePopup,
+    PopupClosed(Id),
+}
+
+impl Applet {
+    /// Copies `rgb` in the default format and records it in the pick history
+    fn copy(&mut self, kind: EntryKind, rgb: [f32; 3]) -> Task<Message> {
+        let space = ColorSpace::from_combo(self.settings.default_space, rgb, &self.conversion);
+        let contents = self.settings.copy_format.format(&space, &self.conversion);
+
+        // The app may have changed the history since it was loaded
+        if let Some(state) = &self.state {
+            self.pick_history = PickHistory::load(state);
+        }
+        self.pick_history.push(Entry::new(
+            kind,
+            rgb,
+            space.combo(),
+            self.settings.copy_format,
+            contents.clone(),
+        ));
+        if let Some(state) = &self.state {
+            self.pick_history.save(state);
+        }
+
+        log::info!("Copying \"{contents}\" to clipboard");
+        clipboard::write(contents)
+    }
+}
+
+impl Application for Applet {
+    type Executor = cosmic::executor::Default;
+
+    type Flags = ();
+
+    type Message = Message;
+
+    const APP_ID: &'static str = "io.github.pixeldoted.cosmic-ext-color-picker.Applet";
+
+    fn core(&self) -> &Core {
+        &self.core
+    }
+
+    fn core_mut(&mut self) -> &mut Core {
+        &mut self.core
+    }
+
+    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Self::Message>) {
+        let state = match cosmic_config::Config::new_state(ColorPicker::APP_ID, STATE_VERSION) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                log::error!("Failed to open state: {e}");
+                None
+            }
+        };
+        let mut settings = match cosmic_config::Config::new(ColorPicker::APP_ID, CONFIG_VERSION) {
+            Ok(config) => Settings::load(&config),
+            Err(e) => {
+                log::error!("Failed to open config: {e}");
+                Settings::default()
+            }
+        };
+
+        let conversion = settings.conversion();
+
+        let applet = Applet {
+            core,
+            popup: None,
+            settings,
+            conversion,
+            pick_history: state.as_ref().map(PickHistory::load).unwrap_or_default(),
+            state,
+        };
+        (applet, Task::none())
+    }
+
+    fn on_close_requested(&self, id: Id) -> Option<Message> {
+        Some(Message::PopupClosed(id))
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::None => (),
+            Message::Pick => {
+                let close = match self.popup.take() {
+                    Some(popup) => destroy_popup(popup),
+                    None => Task::none(),
+                };
+                let pick = cosmic::task::future(async move {
+                    match portal::pick_color().await {
+                        Some(rgb) => Message::PickResponse(rgb),
+                        None => Message::None,
+                    }
+                });
+                return close.chain(pick);
+            }
+            Message::PickResponse(rgb) => {
+                return self.copy(EntryKind::Picked, rgb);
+            }
+            Message::Copy(index) => {
+                if let Some(rgb) = self.pick_history.entries.get(index).map(|e| e.rgb) {
+                    return self.copy(EntryKind::Copied, rgb);
+                }
+            }
+            Message::OpenApp => {
+                let spawned =
+                    std::env::current_exe().and_then(|exe| std::process::Command::new(exe).spawn());
+                if let Err(e) = spawned {
+                    log::error!("Failed to open the color picker: {e}");
+                }
+            }
+            Message::TogglePopup => {
+                if let Some(popup) = self.popup.take() {
+                    return destroy_popup(popup);
+                }
+                let Some(parent) = self.core.main_window_id() else {
+                    return Task::none();
+                };
+
+                if let Some(state) = &self.state {
+                    self.pick_history = PickHistory::load(state);
+                }
+
+                let popup = Id::unique();
+                self.popup = Some(popup);
+                let mut popup_settings = self
+                    .core
+                    .applet
+                    .get_popup_settings(parent, popup, None, None, None);
+                popup_settings.positioner.size_limits = Limits::NONE
+                    .min_width(300.0)
+                    .max_width(372.0)
+                    .min_height(100.0)
+                    .max_height(600.0);
+                return get_popup(popup_settings);
+            }
+            Message::PopupClosed(id) => {
+                if self.popup == Some(id) {
+                    self.popup = None;
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        // Left click picks right away, right click shows the recent colors
+        mouse_area(self.core.applet.icon_button(ICON).on_press(Message::Pick))
+            .on_right_press(Message::TogglePopup)
+            .into()
+    }
+
+    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
+        let mut list = widget::ListColumn::new();
+        let picked = self
+            .pick_history
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.kind == EntryKind::Picked)
+            .take(RECENT)
+            .collect::<Vec<_>>();
+        let empty = picked.is_empty();
+        for (index, entry) in picked {
+            list = list.add(
+                widget::button::custom(
+                    widget::row::with_capacity(2)
+                        .push(
+                            color_block(
+                                Color::from_rgb(entry.rgb[0], entry.rgb[1], entry.rgb[2]),
+                                None,
+                            )
+                            .width(32.0)
+                            .height(32.0),
+                        )
+                        .push(widget::text::body(entry.text.clone()).width(Length::Fill))
+                        .align_y(Vertical::Center)
+                        .spacing(10.0),
+                )
+                .class(cosmic::theme::Button::MenuItem)
+                .on_press(Message::Copy(index))
+                .width(Length::Fill),
+            );
+        }
+
+        let recent: Element<'_, Message> = if empty {
+            widget::text::body(fl!("history-empty")).into()
+        } else {
+            list.into()
+        };
+
+        let content = widget::column::with_capacity(3)
+            .push(
+                widget::button::standard(fl!("pick-screen"))
+                    .on_press(Message::Pick)
+                    .width(Length::Fill),
+            )
+            .push(recent)
+            .push(
+                widget::button::text(fl!("open-app"))
+                    .on_press(Message::OpenApp)
+                    .width(Length::Fill),
+            )
+            .spacing(10.0)
+            .padding(10.0);
+
+        self.core.applet.popup_container(content).into()
+    }
+
+    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
+        Some(cosmic::applet::style())
+    }
+}
@@ -1,28 +1,122 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+mod channel;
 mod cmyk;
+mod hdr;
 mod hsv;
+mod icc;
 mod oklab;
 mod oklch;
+mod operation;
 mod rgb;
+#[cfg(test)]
+pub(crate) mod test;
 
 use std::fmt::Display;
+use std::rc::Rc;
 
+use serde::{Deserialize, Serialize};
+
+pub use channel::{Channel, Field, Unit};
 pub use cmyk::Cmyk;
+pub use hdr::{luminance, srgb_from_linear, srgb_to_linear, tone_map, Encoding, Hdr, SDR_WHITE};
 pub use hsv::Hsv;
-pub use oklab::Oklab;
+pub use icc::{CmykProfile, Intent};
+pub use oklab::{oklab_to_rgb, rgb_to_oklab, Oklab};
 pub use oklch::Oklch;
-pub use rgb::Rgb;
+pub use operation::{mix, Operation};
+pub use rgb::{parse_srgb, Rgb};
+
+use crate::{display::DisplayProfile, fl, proof::Proof};
+
+/// Chroma or saturation below which a color's hue is undefined
+const ACHROMATIC: f32 = 1e-4;
+
+/// Decimal places kept when showing as many as needed, hides float noise
+const AUTO_PRECISION: usize = 4;
+
+/// Settings that change how channels are shown and edited
+#[derive(Clone)]
+pub struct Context {
+    /// Decimal places of channel strings, `None` shows as many as needed
+    pub precision: Option<u32>,
+    /// How 0-1 channels are shown and typed, unless the column overrides it
+    pub unit: Unit,
+    /// Fraction of a slider's range moved per scroll step
+    pub scroll_step: f32,
+    /// Total ink coverage in percent above which CMYK columns show a warning
+    pub ink_limit: u32,
+    /// Profile of the monitor that sliders and graphs are shown through,
+    /// `None` shows them unmanaged
+    pub display: Option<Rc<DisplayProfile>>,
+    /// Target the graphs are soft proofed on, `None` when soft proofing is off
+    pub proof: Option<Rc<Proof>>,
+}
 
-use crate::fl;
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            unit: Unit::default(),
+            scroll_step: 0.01,
+            ink_limit: 300,
+            display: None,
+            proof: None,
+        }
+    }
+}
+
+/// What conversions from RGB depend on besides the color
+#[derive(Clone)]
+pub struct Conversion {
+    /// Output profile of new CMYK columns, `None` uses the naive formula
+    pub cmyk_profile: Option<Rc<CmykProfile>>,
+    /// Luminance in cd/m² of an RGB value of 1 in new HDR columns
+    pub sdr_white: u32,
+}
+
+impl Default for Conversion {
+    fn default() -> Self {
+        Self {
+            cmyk_profile: None,
+            sdr_white: SDR_WHITE,
+        }
+    }
+}
+
+/// Formats a channel value for its text field with `precision` decimal places,
+/// `None` shows as many as needed
+pub fn format_value(value: f32, precision: Option<u32>) -> String {
+    match precision {
+        None => {
+            let string = format!("{value:.AUTO_PRECISION$}");
+            let string = string.trim_end_matches('0').trim_end_matches('.');
+            match string {
+                "-0" => "0".to_string(),
+                string => string.to_string(),
+            }
+        }
+        Some(precision) => format!("{value:.*}", precision as usize),
+    }
+}
 
 #[derive(Clone, Debug)]
 pub enum ColorSpaceMessage {
-    ChangeValue { index: usize, value: f32 },
-    ChangeString { index: usize, string: String },
+    ChangeValue {
+        index: usize,
+        value: f32,
+    },
+    ChangeString {
+        index: usize,
+        string: String,
+    },
+    /// Applies pending input of a channel field, e.g. a relative expression
+    SubmitString {
+        index: usize,
+    },
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ColorSpaceCombo {
     #[default]
     Rgb,
@@ -30,6 +124,7 @@ pub enum ColorSpaceCombo {
     Oklab,
     Oklch,
     Cmyk,
+    Hdr,
 }
 
 impl Display for ColorSpaceCombo {
@@ -40,16 +135,19 @@ impl Display for ColorSpaceCombo {
             ColorSpaceCombo::Oklab => f.write_str(&fl!("oklab")),
             ColorSpaceCombo::Oklch => f.write_str(&fl!("oklch")),
             ColorSpaceCombo::Cmyk => f.write_str(&fl!("cmyk")),
+            ColorSpaceCombo::Hdr => f.write_str(&fl!("hdr")),
         }
     }
 }
 
+#[derive(Clone)]
 pub enum ColorSpace {
     Rgb(Rgb),
     Hsv(Hsv),
     Oklab(Oklab),
     Oklch(Oklch),
     Cmyk(Cmyk),
+    Hdr(Hdr),
 }
 
 impl Default for ColorSpace {
@@ -59,28 +157,100 @@ impl Default for ColorSpace {
 }
 
 impl ColorSpace {
+    /// Channel, value and text field at `index`
+    fn channel_mut(&mut self, index: usize) -> Option<(Channel, &mut f32, &mut Field)> {
+        let (channel, values, fields): (_, &mut [f32], &mut [Field]) = match self {
+            ColorSpace::Rgb(rgb) => (
+                rgb.channels().get(index).copied(),
+                &mut rgb.values,
+                &mut rgb.fields,
+            ),
+            ColorSpace::Hsv(hsv) => (
+                Hsv::CHANNELS.get(index).copied(),
+                &mut hsv.values,
+                &mut hsv.fields,
+            ),
+            ColorSpace::Oklab(oklab) => (
+                Oklab::CHANNELS.get(index).copied(),
+                &mut oklab.values,
+                &mut oklab.fields,
+            ),
+            ColorSpace::Oklch(oklch) => (
+                Oklch::CHANNELS.get(index).copied(),
+                &mut oklch.values,
+                &mut oklch.fields,
+            ),
+            ColorSpace::Cmyk(cmyk) => (
+                cmyk.channels().get(index).copied(),
+                &mut cmyk.values,
+                &mut cmyk.fields,
+            ),
+            ColorSpace::Hdr(hdr) => (
+                Some(hdr.encoding.channel()),
+                &mut hdr.values,
+                &mut hdr.fields,
+            ),
+        };
+
+        Some((channel?, values.get_mut(index)?, fields.get_mut(index)?))
+    }
+
     pub fn change_value(&mut self, index: usize, value: f32) {
-        match self {
-            ColorSpace::Rgb(rgb) => rgb.change_value(index, value),
-            ColorSpace::Hsv(hsv) => hsv.change_value(index, value),
-            ColorSpace::Oklab(oklab) => oklab.change_value(index, value),
-            ColorSpace::Oklch(oklch) => oklch.change_value(index, value),
-            ColorSpace::Cmyk(cmyk) => cmyk.change_value(index, value),
+        if let Some((_, current, field)) = self.channel_mut(index) {
+            *current = value;
+            *field = Field::default();
         }
     }
 
-    pub fn change_string(&mut self, index: usize, string: String) {
-        match self {
-            ColorSpace::Rgb(rgb) => rgb.change_string(index, string),
-            ColorSpace::Hsv(hsv) => hsv.change_string(index, string),
-            ColorSpace::Oklab(oklab) => oklab.change_string(index, string),
-            ColorSpace::Oklch(oklch) => oklch.change_string(index, string),
-            ColorSpace::Cmyk(cmyk) => cmyk.change_string(index, string),
+    pub fn change_string(&mut self, ctx: &Context, index: usize, string: String) {
+        if let Some((channel, value, field)) = self.channel_mut(index) {
+            channel.change_string(ctx, value, field, string);
+        }
+    }
+
+    pub fn submit_string(&mut self, ctx: &Context, index: usize) {
+        if let Some((channel, value, field)) = self.channel_mut(index) {
+            channel.submit(ctx, value, field);
         }
     }
 }
 
 impl ColorSpace {
+    pub fn from_combo(combo: ColorSpaceCombo, rgb: [f32; 3], conversion: &Conversion) -> Self {
+        match combo {
+            ColorSpaceCombo::Rgb => Self::Rgb(Rgb::from_rgb(rgb)),
+            ColorSpaceCombo::Hsv => Self::Hsv(Hsv::from_rgb(rgb)),
+            ColorSpaceCombo::Oklab => Self::Oklab(Oklab::from_rgb(rgb)),
+            ColorSpaceCombo::Oklch => Self::Oklch(Oklch::from_rgb(rgb)),
+            ColorSpaceCombo::Cmyk => {
+                Self::Cmyk(Cmyk::from_rgb(rgb, conversion.cmyk_profile.clone()))
+            }
+            ColorSpaceCombo::Hdr => Self::Hdr(Hdr::from_rgb(rgb, conversion.sdr_white)),
+        }
+    }
+
+    pub fn combo(&self) -> ColorSpaceCombo {
+        match self {
+            ColorSpace::Rgb(_) => ColorSpaceCombo::Rgb,
+            ColorSpace::Hsv(_) => ColorSpaceCombo::Hsv,
+            ColorSpace::Oklab(_) => ColorSpaceCombo::Oklab,
+            ColorSpace::Oklch(_) => ColorSpaceCombo::Oklch,
+            ColorSpace::Cmyk(_) => ColorSpaceCombo::Cmyk,
+            ColorSpace::Hdr(_) => ColorSpaceCombo::Hdr,
+        }
+    }
+
+    pub fn copy_to_clipboard(&self) -> String {
+        match self {
+            ColorSpace::Rgb(rgb) => rgb.copy_to_clipboard(),
+            ColorSpace::Hsv(hsv) => hsv.copy_to_clipboard(),
+            ColorSpace::Oklab(oklab) => oklab.copy_to_clipboard(),
+            ColorSpace::Oklch(oklch) => oklch.copy_to_clipboard(),
+            ColorSpace::Cmyk(cmyk) => cmyk.copy_to_clipboard(),
+            ColorSpace::Hdr(hdr) => hdr.copy_to_clipboard(),
+        }
+    }
+
     pub fn to_rgb(&self) -> ColorSpace {
         let rgb = self.get_rgb();
         Self::Rgb(Rgb::from_rgb(rgb))
@@ -101,18 +271,107 @@ impl ColorSpace {
         Self::Oklch(Oklch::from_rgb(rgb))
     }
 
-    pub fn to_cmyk(&self) -> ColorSpace {
+    pub fn to_cmyk(&self, conversion: &Conversion) -> ColorSpace {
+        let rgb = self.get_rgb();
+        Self::Cmyk(Cmyk::from_rgb(rgb, conversion.cmyk_profile.clone()))
+    }
+
+    pub fn to_hdr(&self, conversion: &Conversion) -> ColorSpace {
         let rgb = self.get_rgb();
-        Self::Cmyk(Cmyk::from_rgb(rgb))
+        Self::Hdr(Hdr::from_rgb(rgb, conversion.sdr_white))
     }
 
     pub fn convert_from_rgb(&mut self, rgb_in: [f32; 3]) {
         match self {
-            ColorSpace::Rgb(rgb) => *rgb = rgb::Rgb::from_rgb(rgb_in),
+            ColorSpace::Rgb(rgb) => {
+                let unit = rgb.unit;
+                *rgb = rgb::Rgb::from_rgb(rgb_in);
+                rgb.set_unit(unit);
+            }
             ColorSpace::Hsv(hsv) => *hsv = hsv::Hsv::from_rgb(rgb_in),
             ColorSpace::Oklab(oklab) => *oklab = oklab::Oklab::from_rgb(rgb_in),
             ColorSpace::Oklch(oklch) => *oklch = oklch::Oklch::from_rgb(rgb_in),
-            ColorSpace::Cmyk(cmyk) => *cmyk = cmyk::Cmyk::from_rgb(rgb_in),
+            ColorSpace::Cmyk(cmyk) => {
+                let unit = cmyk.unit;
+                *cmyk = cmyk::Cmyk::from_rgb(rgb_in, cmyk.profile.clone());
+                cmyk.set_unit(unit);
+            }
+            ColorSpace::Hdr(hdr) => {
+                *hdr = hdr::Hdr::from_rgb_with(rgb_in, hdr.encoding, hdr.sdr_white);
+            }
+        }
+    }
+
+    /// Like [`ColorSpace::convert_from_rgb`],
+    /// but keeps channels the new color leaves undefined (e.g. hue of a gray)
+    pub fn sync_from_rgb(&mut self, rgb_in: [f32; 3]) {
+        match self {
+            ColorSpace::Hsv(hsv) => hsv.sync_from_rgb(rgb_in),
+            ColorSpace::Oklch(oklch) => oklch.sync_from_rgb(rgb_in),
+            _ => self.convert_from_rgb(rgb_in),
+        }
+    }
+
+    /// Drops text typed into the channel fields, e.g. after the unit changed
+    pub fn reset_fields(&mut self) {
+        match self {
+            ColorSpace::Rgb(rgb) => rgb.fields = Default::default(),
+            ColorSpace::Hsv(hsv) => hsv.fields = Default::default(),
+            ColorSpace::Oklab(oklab) => oklab.fields = Default::default(),
+            ColorSpace::Oklch(oklch) => oklch.fields = Default::default(),
+            ColorSpace::Cmyk(cmyk) => cmyk.fields = Default::default(),
+            ColorSpace::Hdr(hdr) => hdr.fields = Default::default(),
+        }
+    }
+
+    /// Unit of the channels if the space can override the global unit,
+    /// the inner `None` follows the global unit
+    pub fn unit(&self) -> Option<Option<Unit>> {
+        match self {
+            ColorSpace::Rgb(rgb) => Some(rgb.unit),
+            ColorSpace::Cmyk(cmyk) => Some(cmyk.unit),
+            _ => None,
+        }
+    }
+
+    pub fn set_unit(&mut self, unit: Option<Unit>) {
+        match self {
+            ColorSpace::Rgb(rgb) => rgb.set_unit(unit),
+            ColorSpace::Cmyk(cmyk) => cmyk.set_unit(unit),
+            _ => (),
+        }
+    }
+
+    /// Encoding of the channels if the space is an HDR one
+    pub fn encoding(&self) -> Option<Encoding> {
+        match self {
+            ColorSpace::Hdr(hdr) => Some(hdr.encoding),
+            _ => None,
+        }
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        if let ColorSpace::Hdr(hdr) = self {
+            hdr.set_encoding(encoding);
+        }
+    }
+
+    /// Luminance in cd/m² of an RGB value of 1 in HDR columns
+    pub fn sdr_white(&self) -> Option<u32> {
+        match self {
+            ColorSpace::Hdr(hdr) => Some(hdr.sdr_white),
+            _ => None,
+        }
+    }
+
+    pub fn values(&self) -> &[f32] {
+        match self {
+            ColorSpace::Rgb(rgb) => &rgb.values,
+            ColorSpace::Hsv(hsv) => &hsv.values,
+            ColorSpace::Oklab(oklab) => &oklab.values,
+            ColorSpace::Oklch(oklch) => &oklch.values,
+            ColorSpace::Cmyk(cmyk) => &cmyk.values,
+            ColorSpace::Hdr(hdr) => &hdr.values,
         }
     }
 
@@ -123,6 +382,7 @@ impl ColorSpace {
             ColorSpace::Oklab(oklab) => oklab.to_rgb(),
             ColorSpace::Oklch(oklch) => oklch.to_rgb(),
             ColorSpace::Cmyk(cmyk) => cmyk.to_rgb(),
+            ColorSpace::Hdr(hdr) => hdr.to_rgb(),
         }
     }
 }
@@ -0,0 +1,422 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use cosmic::{
+    iced::{gradient::ColorStop, Alignment},
+    widget,
+};
+use serde::{Deserialize, Serialize};
+
+use super::Context;
+use crate::{
+    colorspace::ColorSpaceMessage as Message,
+    fl,
+    widgets::{channel_input, color_slider},
+};
+
+/// How 0-1 channels are shown and typed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unit {
+    /// 0-1
+    #[default]
+    Fraction,
+    /// 0-100%
+    Percent,
+    /// 0-255
+    Byte,
+    /// 0-65535
+    Word,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 4] = [Unit::Fraction, Unit::Percent, Unit::Byte, Unit::Word];
+
+    fn scale(self) -> f32 {
+        match self {
+            Unit::Fraction => 1.0,
+            Unit::Percent => 100.0,
+            Unit::Byte => 255.0,
+            Unit::Word => 65535.0,
+        }
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Fraction => f.write_str(&fl!("unit-fraction")),
+            Unit::Percent => f.write_str(&fl!("unit-percent")),
+            Unit::Byte => f.write_str(&fl!("unit-byte")),
+            Unit::Word => f.write_str(&fl!("unit-word")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    Invalid,
+    OutOfRange,
+}
+
+/// Range and formatting of a color space channel
+#[derive(Clone, Copy, Debug)]
+pub struct Channel {
+    pub min: f32,
+    pub max: f32,
+    /// Angles wrap around instead of going out of range, and are shown in degrees
+    pub angle: bool,
+    /// Typed values may go above `max`, which only bounds the slider
+    pub open: bool,
+    /// Unit overriding the one of the settings for 0-1 channels
+    pub unit: Option<Unit>,
+}
+
+impl Channel {
+    pub const fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            angle: false,
+            open: false,
+            unit: None,
+        }
+    }
+
+    pub const fn angle() -> Self {
+        Self {
+            min: 0.0,
+            max: 360.0,
+            angle: true,
+            open: false,
+            unit: None,
+        }
+    }
+
+    /// Accepts typed values above `max`, e.g. extended RGB
+    pub const fn open(self) -> Self {
+        Self { open: true, ..self }
+    }
+
+    pub const fn with_unit(self, unit: Option<Unit>) -> Self {
+        Self { unit, ..self }
+    }
+
+    pub fn range(&self) -> RangeInclusive<f32> {
+        self.min..=self.max
+    }
+
+    fn unit(&self, ctx: &Context) -> Unit {
+        #[allow(clippy::float_cmp)]
+        if self.min == 0.0 && self.max == 1.0 {
+            self.unit.unwrap_or(ctx.unit)
+        } else {
+            Unit::Fraction
+        }
+    }
+
+    /// Formats `value` for the channel's text field
+    pub fn format(&self, value: f32, ctx: &Context) -> String {
+        let unit = self.unit(ctx);
+        let shown = value * unit.scale();
+        let precision = ctx.precision;
+        match unit {
+            _ if self.angle => format!("{}°", super::format_value(shown, precision)),
+            Unit::Fraction => super::format_value(shown, precision),
+            Unit::Percent => format!("{}%", super::format_value(shown, precision)),
+            #[allow(clippy::cast_possible_truncation)]
+            Unit::Byte | Unit::Word => (shown.round() as i32).to_string(),
+        }
+    }
+
+    /// Whether `input` should wait for submit before being applied,
+    /// either because it's a relative expression or an incomplete number
+    pub fn is_pending(&self, input: &str) -> bool {
+        let input = input.trim();
+        input.is_empty() || input == "-" || input == "." || self.operator(input).is_some()
+    }
+
+    /// Returns the operator of a relative expression like `+10` or `*0.5`
+    fn operator(&self, input: &str) -> Option<char> {
+        match input.chars().next()? {
+            op @ ('+' | '*' | '/') => Some(op),
+            // Only channels that can't be negative subtract
+            '-' if self.min >= 0.0 => Some('-'),
+            _ => None,
+        }
+    }
+
+    /// Parses `input` typed into the channel's text field.
+    /// Relative expressions like `+10`, `-5`, `*0.5` or `/2` are applied to `current`
+    pub fn parse(&self, input: &str, current: f32, ctx: &Context) -> Result<f32, Error> {
+        let unit = self.unit(ctx);
+        let input = input.trim();
+        let input = input
+            .strip_suffix('°')
+            .filter(|_| self.angle)
+            .or_else(|| input.strip_suffix('%').filter(|_| unit == Unit::Percent))
+            .unwrap_or(input)
+            .trim_end();
+
+        let operator = self.operator(input);
+        let number: f32 = match operator {
+            Some(_) => input[1..].trim_start(),
+            None => input,
+        }
+        .parse()
+        .map_err(|_| Error::Invalid)?;
+
+        let scale = unit.scale();
+        let value = match operator {
+            Some('+') => current + number / scale,
+            Some('-') => current - number / scale,
+            Some('*') => current * number,
+            Some('/') if number != 0.0 => current / number,
+            Some(_) => return Err(Error::Invalid),
+            None => number / scale,
+        };
+
+        if !value.is_finite() {
+            return Err(Error::Invalid);
+        }
+
+        if self.angle {
+            return Ok(value.rem_euclid(self.max));
+        }
+
+        // Allow for float noise at the edges
+        const EPSILON: f32 = 1e-4;
+        let max = if self.open { f32::INFINITY } else { self.max };
+        if value < self.min - EPSILON || value > max + EPSILON {
+            return Err(Error::OutOfRange);
+        }
+
+        Ok(value.clamp(self.min, max))
+    }
+
+    /// Applies `input` typed into the channel's text field to `value`,
+    /// returns whether the input is invalid.
+    /// Pending input is only applied when `submit` is set
+    pub fn edit(&self, value: &mut f32, input: &str, submit: bool, ctx: &Context) -> bool {
+        if !submit && self.is_pending(input) {
+            return false;
+        }
+
+        match self.parse(input, *value, ctx) {
+            Ok(parsed) => {
+                *value = parsed;
+                false
+            }
+            Err(_) => true,
+        }
+    }
+
+    /// Applies `string` typed into the channel's text field to `value`
+    pub fn change_string(&self, ctx: &Context, value: &mut f32, field: &mut Field, string: String) {
+        let invalid = self.edit(value, &string, false, ctx);
+        *field = Field {
+            edit: Some(string),
+            invalid,
+        };
+    }
+
+    /// Applies pending input like `+10` and shows the value formatted again
+    pub fn submit(&self, ctx: &Context, value: &mut f32, field: &mut Field) {
+        let Some(edit) = &field.edit else {
+            return;
+        };
+        field.invalid = self.edit(value, edit, true, ctx);
+        if !field.invalid {
+            field.edit = None;
+        }
+    }
+
+    /// Error shown under an invalid text field
+    pub fn error(&self, ctx: &Context) -> String {
+        if self.open {
+            return fl!("channel-invalid-min", min = self.format(self.min, ctx));
+        }
+
+        fl!(
+            "channel-invalid",
+            min = self.format(self.min, ctx),
+            max = self.format(self.max, ctx)
+        )
+    }
+
+    /// Name, text field and slider of the channel at `index`
+    pub fn view<'a>(
+        &self,
+        ctx: &Context,
+        index: usize,
+        name: String,
+        value: f32,
+        field: &Field,
+        color_stops: &'static [ColorStop],
+    ) -> widget::Column<'a, Message> {
+        widget::column::with_capacity(3)
+            .push(
+                widget::row::with_capacity(2)
+                    .push(widget::text(name).size(20.0))
+                    .push(channel_input(
+                        &field.text(self, value, ctx),
+                        field.invalid.then(|| self.error(ctx)),
+                        move |string| Message::ChangeString { index, string },
+                        Message::SubmitString { index },
+                    ))
+                    .align_y(Alignment::Center)
+                    .spacing(10.0),
+            )
+            .push(color_slider(
+                self.range(),
+                value,
+                move |value| Message::ChangeValue { index, value },
+                color_stops,
+                ctx.scroll_step,
+                ctx.display.as_deref(),
+            ))
+            .spacing(10.0)
+            .padding(10.0)
+    }
+}
+
+/// Text field of a color space channel
+#[derive(Clone, Debug, Default)]
+pub struct Field {
+    /// Text typed into the field, `None` shows the channel's value
+    pub edit: Option<String>,
+    /// Whether `edit` is invalid
+    pub invalid: bool,
+}
+
+impl Field {
+    /// Text shown in the field, the typed text or else `value` formatted
+    pub fn text(&self, channel: &Channel, value: f32, ctx: &Context) -> String {
+        match &self.edit {
+            Some(edit) => edit.clone(),
+            None => channel.format(value, ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Channel, Error, Unit};
+    use crate::colorspace::Context;
+
+    const UNIT: Channel = Channel::new(0.0, 1.0);
+    const SIGNED: Channel = Channel::new(-0.5, 0.5);
+    const HUE: Channel = Channel::angle();
+
+    #[test]
+    fn absolute() {
+        let ctx = Context::default();
+        assert_eq!(UNIT.parse(" 0.25 ", 1.0, &ctx), Ok(0.25));
+        assert_eq!(SIGNED.parse("-0.25", 0.0, &ctx), Ok(-0.25));
+        assert_eq!(HUE.parse("120°", 0.0, &ctx), Ok(120.0));
+    }
+
+    #[test]
+    fn relative() {
+        let ctx = Context::default();
+        assert_eq!(UNIT.parse("+0.25", 0.5, &ctx), Ok(0.75));
+        assert_eq!(UNIT.parse("-0.25", 0.5, &ctx), Ok(0.25));
+        assert_eq!(UNIT.parse("*0.5", 0.5, &ctx), Ok(0.25));
+        assert_eq!(UNIT.parse("/ 2", 0.5, &ctx), Ok(0.25));
+        assert_eq!(UNIT.parse("/0", 0.5, &ctx), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn hue_wraps() {
+        let ctx = Context::default();
+        assert_eq!(HUE.parse("720", 0.0, &ctx), Ok(0.0));
+        assert_eq!(HUE.parse("+30", 350.0, &ctx), Ok(20.0));
+        assert_eq!(HUE.parse("-30", 10.0, &ctx), Ok(340.0));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let ctx = Context::default();
+        assert_eq!(UNIT.parse("3.0", 0.0, &ctx), Err(Error::OutOfRange));
+        assert_eq!(UNIT.parse("+1", 0.5, &ctx), Err(Error::OutOfRange));
+        assert_eq!(SIGNED.parse("-0.6", 0.0, &ctx), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn open() {
+        let ctx = Context::default();
+        let open = UNIT.open();
+        assert_eq!(open.parse("3.0", 0.0, &ctx), Ok(3.0));
+        assert_eq!(open.parse("+1", 0.5, &ctx), Ok(1.5));
+        assert_eq!(open.parse("-0.1", 0.0, &ctx), Err(Error::OutOfRange));
+        assert_eq!(open.range(), 0.0..=1.0);
+    }
+
+    #[test]
+    fn invalid() {
+        let ctx = Context::default();
+        assert_eq!(UNIT.parse("abc", 0.0, &ctx), Err(Error::Invalid));
+        assert_eq!(UNIT.parse("NaN", 0.0, &ctx), Err(Error::Invalid));
+        assert_eq!(UNIT.parse("50%", 0.0, &ctx), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn pending() {
+        let ctx = Context::default();
+        assert!(UNIT.is_pending("+1"));
+        assert!(UNIT.is_pending("-"));
+        assert!(!UNIT.is_pending("0.5"));
+        assert!(!SIGNED.is_pending("-0.1"));
+
+        let mut value = 0.5;
+        assert!(!UNIT.edit(&mut value, "*2", false, &ctx));
+        assert_eq!(value, 0.5);
+        assert!(!UNIT.edit(&mut value, "*2", true, &ctx));
+        assert_eq!(value, 1.0);
+        assert!(UNIT.edit(&mut value, "2", false, &ctx));
+        assert_eq!(value, 1.0);
+    }
+
+    #[test]
+    fn format() {
+        let ctx = Context::default();
+        assert_eq!(UNIT.format(0.3, &ctx), "0.3");
+        assert_eq!(HUE.format(90.0, &ctx), "90°");
+
+        let ctx = Context {
+            precision: Some(2),
+            ..Context::default()
+        };
+        assert_eq!(UNIT.format(0.3, &ctx), "0.30");
+        assert_eq!(HUE.format(90.0, &ctx), "90.00°");
+    }
+
+    #[test]
+    fn unit_override() {
+        let ctx = Context::default();
+        let word = UNIT.with_unit(Some(Unit::Word));
+        assert_eq!(word.format(1.0, &ctx), "65535");
+        assert_eq!(word.parse("32767.5", 0.0, &ctx), Ok(0.5));
+        assert_eq!(word.parse("+6553.5", 0.5, &ctx), Ok(0.6));
+
+        let percent = UNIT.with_unit(Some(Unit::Percent));
+        assert_eq!(percent.format(0.25, &ctx), "25%");
+        assert_eq!(percent.parse("50%", 0.0, &ctx), Ok(0.5));
+        assert_eq!(percent.parse("101", 0.0, &ctx), Err(Error::OutOfRange));
+
+        let ctx = Context {
+            unit: Unit::Byte,
+            ..Context::default()
+        };
+        assert_eq!(UNIT.format(1.0, &ctx), "255");
+        assert_eq!(UNIT.parse("51", 0.0, &ctx), Ok(0.2));
+        assert_eq!(word.format(1.0, &ctx), "65535");
+
+        // Only 0-1 channels take a unit
+        assert_eq!(
+            SIGNED.with_unit(Some(Unit::Byte)).format(0.25, &ctx),
+            "0.25"
+        );
+    }
+}
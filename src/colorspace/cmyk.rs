@@ -1,11 +1,19 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::rc::Rc;
+
 use cosmic::{
-    iced::{gradient::ColorStop, Alignment, Color},
-    widget,
+    iced::{gradient::ColorStop, Color},
+    theme, widget,
 };
 
-use crate::{colorspace::ColorSpaceMessage as Message, fl, widgets::color_slider};
+use super::{Channel, CmykProfile, Context, Field, Unit};
+use crate::{colorspace::ColorSpaceMessage as Message, fl};
+
+/// Sum of the inks in percent, up to 400%
+pub fn ink_coverage(cmyk: &[f32; 4]) -> f32 {
+    cmyk.iter().sum::<f32>() * 100.0
+}
 
 const COLOR_STOPS_CYAN: [ColorStop; 2] = [
     ColorStop {
@@ -51,40 +59,64 @@ const COLOR_STOPS_BLACK: [ColorStop; 2] = [
 #[derive(Clone)]
 pub struct Cmyk {
     pub values: [f32; 4],
-    pub strings: [String; 4],
+    /// Text fields of the channels
+    pub fields: [Field; 4],
+    /// Unit of the channels, `None` follows the global unit
+    pub unit: Option<Unit>,
+    /// Output profile the inks are for, `None` uses the naive formula
+    pub profile: Option<Rc<CmykProfile>>,
 }
 
 impl Default for Cmyk {
     fn default() -> Self {
         Self {
             values: [0.0; 4],
-            strings: ["0".into(), "0".into(), "0".into(), "0".into()],
+            fields: Default::default(),
+            unit: None,
+            profile: None,
         }
     }
 }
 
 impl Cmyk {
-    pub fn from_rgb(rgb: [f32; 3]) -> Self {
-        let cmyk = rgb_to_cmyk(rgb[0], rgb[1], rgb[2]);
+    pub const CHANNELS: [Channel; 4] = [
+        Channel::new(0.0, 1.0),
+        Channel::new(0.0, 1.0),
+        Channel::new(0.0, 1.0),
+        Channel::new(0.0, 1.0),
+    ];
+
+    pub fn from_rgb(rgb: [f32; 3], profile: Option<Rc<CmykProfile>>) -> Self {
+        let cmyk = match &profile {
+            Some(profile) => profile.rgb_to_cmyk(rgb),
+            None => rgb_to_cmyk(rgb[0], rgb[1], rgb[2]),
+        };
 
         Self {
-            strings: [
-                cmyk[0].to_string(),
-                cmyk[1].to_string(),
-                cmyk[2].to_string(),
-                cmyk[3].to_string(),
-            ],
             values: cmyk,
+            fields: Default::default(),
+            unit: None,
+            profile,
         }
     }
 
     pub fn to_rgb(&self) -> [f32; 3] {
-        cmyk_to_rgb(
-            self.values[0],
-            self.values[1],
-            self.values[2],
-            self.values[3],
-        )
+        match &self.profile {
+            Some(profile) => profile.cmyk_to_rgb(self.values),
+            None => cmyk_to_rgb(
+                self.values[0],
+                self.values[1],
+                self.values[2],
+                self.values[3],
+            ),
+        }
+    }
+
+    /// Converts the inks to `profile`, keeping the color
+    pub fn set_profile(&mut self, profile: Option<Rc<CmykProfile>>) {
+        let unit = self.unit;
+        *self = Self::from_rgb(self.to_rgb(), profile);
+        self.unit = unit;
     }
 
     pub fn copy_to_clipboard(&self) -> String {
@@ -96,123 +128,69 @@ impl Cmyk {
 }
 
 impl Cmyk {
-    pub fn change_value(&mut self, index: usize, value: f32) {
-        self.values[index] = value;
-        self.strings[index] = value.to_string();
+    /// Channels in the column's unit
+    pub fn channels(&self) -> [Channel; 4] {
+        Self::CHANNELS.map(|channel| channel.with_unit(self.unit))
+    }
+
+    /// Shows the channels in `unit`, `None` follows the global unit
+    pub fn set_unit(&mut self, unit: Option<Unit>) {
+        self.unit = unit;
+        self.fields = Default::default();
     }
 
-    pub fn change_string(&mut self, index: usize, string: String) {
-        if let Ok(value) = string.parse::<f32>() {
-            self.values[index] = value;
+    pub fn view<'a>(&self, ctx: &Context, _show_graphs: bool) -> cosmic::Element<'a, Message> {
+        let channels = self.channels();
+        let names = [fl!("cyan"), fl!("magenta"), fl!("yellow"), fl!("black")];
+        let stops = [
+            &COLOR_STOPS_CYAN,
+            &COLOR_STOPS_MAGENTA,
+            &COLOR_STOPS_YELLOW,
+            &COLOR_STOPS_BLACK,
+        ];
+
+        let mut content = widget::column::with_capacity(5).spacing(10.0);
+        for (index, (name, stops)) in names.into_iter().zip(stops).enumerate() {
+            let card = channels[index].view(
+                ctx,
+                index,
+                name,
+                self.values[index],
+                &self.fields[index],
+                stops,
+            );
+            content = content.push(widget::container(card).class(cosmic::style::Container::Card));
         }
 
-        self.strings[index] = string;
-    }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let coverage = ink_coverage(&self.values).round() as u32;
+        let limit = ctx.ink_limit;
+        let mut ink = widget::column::with_capacity(2)
+            .push(widget::text::body(fl!("ink-coverage", coverage = coverage)))
+            .padding([0.0, 10.0]);
+        if coverage > limit {
+            ink = ink.push(
+                widget::text::caption(fl!("ink-over-limit", limit = limit))
+                    .class(theme::Text::Accent),
+            );
+        }
 
-    pub fn view<'a>(&self, _show_graphs: bool) -> cosmic::Element<'a, Message> {
-        let values = &self.values;
-        let strings = &self.strings;
-
-        let cyan = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("cyan")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[0].clone())
-                            .on_input(|string| Message::ChangeString { index: 0, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[0],
-                |value| Message::ChangeValue { index: 0, value },
-                &COLOR_STOPS_CYAN,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let magenta = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("magenta")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[1].clone())
-                            .on_input(|string| Message::ChangeString { index: 1, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[1],
-                |value| Message::ChangeValue { index: 1, value },
-                &COLOR_STOPS_MAGENTA,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let yellow = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("yellow")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[2].clone())
-                            .on_input(|string| Message::ChangeString { index: 2, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[2],
-                |value| Message::ChangeValue { index: 2, value },
-                &COLOR_STOPS_YELLOW,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let black = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("black")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[3].clone())
-                            .on_input(|string| Message::ChangeString { index: 3, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[3],
-                |value| Message::ChangeValue { index: 3, value },
-                &COLOR_STOPS_BLACK,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-
-        let content = widget::column::with_capacity(3)
-            .push(widget::container(cyan).class(cosmic::style::Container::Card))
-            .push(widget::container(magenta).class(cosmic::style::Container::Card))
-            .push(widget::container(yellow).class(cosmic::style::Container::Card))
-            .push(widget::container(black).class(cosmic::style::Container::Card))
-            .spacing(10.0);
-
-        content.into()
+        content.push(ink).into()
     }
 }
 
 #[allow(clippy::many_single_char_names)]
-fn cmyk_to_rgb(c: f32, m: f32, y: f32, k: f32) -> [f32; 3] {
+pub(super) fn cmyk_to_rgb(c: f32, m: f32, y: f32, k: f32) -> [f32; 3] {
     let inv_k = 1.0 - k;
-    let r = (1.0 - c + k) * inv_k;
-    let g = (1.0 - m + k) * inv_k;
-    let b = (1.0 - y + k) * inv_k;
+    let r = (1.0 - c) * inv_k;
+    let g = (1.0 - m) * inv_k;
+    let b = (1.0 - y) * inv_k;
 
     [r, g, b]
 }
 
 #[allow(clippy::many_single_char_names)]
-fn rgb_to_cmyk(r: f32, g: f32, b: f32) -> [f32; 4] {
+pub(super) fn rgb_to_cmyk(r: f32, g: f32, b: f32) -> [f32; 4] {
     let inv_k = r.max(g).max(b);
     if inv_k <= f32::EPSILON {
         return [0.0, 0.0, 0.0, 1.0];
@@ -228,7 +206,8 @@ fn rgb_to_cmyk(r: f32, g: f32, b: f32) -> [f32; 4] {
 
 #[cfg(test)]
 mod test {
-    use super::{cmyk_to_rgb, rgb_to_cmyk};
+    use super::{cmyk_to_rgb, ink_coverage, rgb_to_cmyk};
+    use crate::colorspace::test::aprox_eq;
 
     #[test]
     fn white() {
@@ -280,11 +259,13 @@ mod test {
         assert!(aprox_eq(&rgb, &[0f32, 0f32, 1f32]));
     }
 
-    fn aprox_eq(a: &[f32], b: &[f32]) -> bool {
-        const EPSILON: f32 = 1e-4;
-
-        a.iter()
-            .zip(b)
-            .all(|(a, b)| *a > *b - EPSILON && *a < *b + EPSILON)
+    #[test]
+    fn coverage() {
+        assert!(aprox_eq(&[ink_coverage(&[0.0; 4])], &[0.0]));
+        assert!(aprox_eq(&[ink_coverage(&[1.0, 1.0, 0.0, 0.0])], &[200.0]));
+        assert!(aprox_eq(
+            &[ink_coverage(&[0.75, 0.68, 0.67, 0.9])],
+            &[300.0]
+        ));
     }
 }
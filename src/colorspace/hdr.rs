@@ -0,0 +1,452 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+
+use cosmic::{
+    iced::{gradient::ColorStop, Color},
+    widget,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Channel, Context, Field};
+use crate::{colorspace::ColorSpaceMessage as Message, fl};
+
+/// Luminance in cd/m² of an RGB value of 1 in BT.2408
+pub const SDR_WHITE: u32 = 203;
+
+/// A luminance setting in cd/m², at least 1 to divide by
+#[allow(clippy::cast_precision_loss)]
+fn as_nits(value: u32) -> f32 {
+    value.max(1) as f32
+}
+
+/// Luminance in cd/m² of 1.0 in scRGB
+const SCRGB_WHITE: f32 = 80.0;
+
+/// Peak luminance in cd/m² of the reference HLG display in BT.2100
+const HLG_PEAK: f32 = 1000.0;
+
+/// System gamma of the HLG OOTF for `HLG_PEAK`
+const HLG_GAMMA: f32 = 1.2;
+
+/// Luminance weights of the Rec.709 primaries
+const REC709_LUMA: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// Luminance weights of the Rec.2020 primaries
+const REC2020_LUMA: [f32; 3] = [0.2627, 0.6780, 0.0593];
+
+#[allow(clippy::excessive_precision, clippy::unreadable_literal)]
+const REC709_TO_REC2020: [[f32; 3]; 3] = [
+    [0.627403895934699, 0.329283038377884, 0.043313065687417],
+    [0.069097289358232, 0.919540395075459, 0.011362315566309],
+    [0.016391438875150, 0.088013307877226, 0.895595253247624],
+];
+
+#[allow(clippy::excessive_precision, clippy::unreadable_literal)]
+const REC2020_TO_REC709: [[f32; 3]; 3] = [
+    [1.660491002108435, -0.587641138788550, -0.072849863319885],
+    [-0.124550474521591, 1.132899897125960, -0.008349422604369],
+    [-0.018150763354905, -0.100578898008007, 1.118729661362913],
+];
+
+/// How an HDR column stores its channels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Encoding {
+    /// Rec.2100 primaries with the ST 2084 curve, absolute up to 10000 cd/m²
+    #[default]
+    Pq,
+    /// Rec.2100 primaries with the hybrid log-gamma curve, relative to the display
+    Hlg,
+    /// Linear Rec.709 primaries where 1.0 is 80 cd/m², negative outside of sRGB
+    ScRgb,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 3] = [Encoding::Pq, Encoding::Hlg, Encoding::ScRgb];
+
+    pub(super) fn channel(self) -> Channel {
+        match self {
+            Encoding::Pq | Encoding::Hlg => Channel::new(0.0, 1.0),
+            // The range of 16-bit scRGB
+            Encoding::ScRgb => Channel::new(-0.5, 7.5),
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encoding::Pq => f.write_str("PQ"),
+            Encoding::Hlg => f.write_str("HLG"),
+            Encoding::ScRgb => f.write_str("scRGB"),
+        }
+    }
+}
+
+const COLOR_STOPS_RED: [ColorStop; 2] = [
+    ColorStop {
+        offset: 0.0,
+        color: Color::from_rgb(0.0, 0.0, 0.0),
+    },
+    ColorStop {
+        offset: 1.0,
+        color: Color::from_rgb(1.0, 0.0, 0.0),
+    },
+];
+const COLOR_STOPS_GREEN: [ColorStop; 2] = [
+    ColorStop {
+        offset: 0.0,
+        color: Color::from_rgb(0.0, 0.0, 0.0),
+    },
+    ColorStop {
+        offset: 1.0,
+        color: Color::from_rgb(0.0, 1.0, 0.0),
+    },
+];
+const COLOR_STOPS_BLUE: [ColorStop; 2] = [
+    ColorStop {
+        offset: 0.0,
+        color: Color::from_rgb(0.0, 0.0, 0.0),
+    },
+    ColorStop {
+        offset: 1.0,
+        color: Color::from_rgb(0.0, 0.0, 1.0),
+    },
+];
+
+#[derive(Clone)]
+pub struct Hdr {
+    pub values: [f32; 3],
+    /// Text fields of the channels
+    pub fields: [Field; 3],
+    pub encoding: Encoding,
+    /// Luminance in cd/m² of an RGB value of 1
+    pub sdr_white: u32,
+}
+
+impl Default for Hdr {
+    fn default() -> Self {
+        Self::from_rgb([1.0; 3], SDR_WHITE)
+    }
+}
+
+impl Hdr {
+    pub fn from_rgb(rgb: [f32; 3], sdr_white: u32) -> Self {
+        Self::from_rgb_with(rgb, Encoding::default(), sdr_white)
+    }
+
+    pub fn from_rgb_with(rgb: [f32; 3], encoding: Encoding, sdr_white: u32) -> Self {
+        let values = encode(encoding, rgb, sdr_white);
+
+        Self {
+            values,
+            fields: Default::default(),
+            encoding,
+            sdr_white,
+        }
+    }
+
+    pub fn to_rgb(&self) -> [f32; 3] {
+        decode(self.encoding, self.values, self.sdr_white)
+    }
+
+    pub fn copy_to_clipboard(&self) -> String {
+        format!("{}, {}, {}", self.values[0], self.values[1], self.values[2])
+    }
+}
+
+impl Hdr {
+    /// Stores the channels in `encoding`, keeping the color
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        *self = Self::from_rgb_with(self.to_rgb(), encoding, self.sdr_white);
+    }
+
+    /// Stores the channels relative to `sdr_white`, keeping the color
+    pub fn set_sdr_white(&mut self, sdr_white: u32) {
+        *self = Self::from_rgb_with(self.to_rgb(), self.encoding, sdr_white);
+    }
+
+    pub fn view<'a>(&self, ctx: &Context, _show_graphs: bool) -> cosmic::Element<'a, Message> {
+        let channel = self.encoding.channel();
+        let names = [fl!("red"), fl!("green"), fl!("blue")];
+        let stops = [&COLOR_STOPS_RED, &COLOR_STOPS_GREEN, &COLOR_STOPS_BLUE];
+
+        let mut content = widget::column::with_capacity(3).spacing(10.0);
+        for (index, (name, stops)) in names.into_iter().zip(stops).enumerate() {
+            let card = channel.view(
+                ctx,
+                index,
+                name,
+                self.values[index],
+                &self.fields[index],
+                stops,
+            );
+            content = content.push(widget::container(card).class(cosmic::style::Container::Card));
+        }
+
+        content.into()
+    }
+}
+
+/// Luminance in cd/m² of `rgb`, where an RGB value of 1 is `sdr_white`
+pub fn luminance(rgb: [f32; 3], sdr_white: u32) -> f32 {
+    dot(REC709_LUMA, rgb.map(srgb_to_linear)) * as_nits(sdr_white)
+}
+
+/// Compresses `rgb` into the SDR range with an extended Reinhard curve on its luminance,
+/// showing `peak` as white where an RGB value of 1 is `sdr_white`
+pub fn tone_map(rgb: [f32; 3], sdr_white: u32, peak: u32) -> [f32; 3] {
+    let linear = rgb.map(|c| srgb_to_linear(c).max(0.0));
+    let luminance = dot(REC709_LUMA, linear);
+    if luminance <= 0.0 {
+        return [0.0; 3];
+    }
+
+    let white = (as_nits(peak) / as_nits(sdr_white)).max(1.0);
+    let mapped = luminance * (1.0 + luminance / (white * white)) / (1.0 + luminance);
+
+    linear.map(|c| srgb_from_linear((c * mapped / luminance).min(1.0)))
+}
+
+/// Channels of `rgb` in `encoding`, where an RGB value of 1 is `sdr_white`
+fn encode(encoding: Encoding, rgb: [f32; 3], sdr_white: u32) -> [f32; 3] {
+    let sdr_white = as_nits(sdr_white);
+    let nits = rgb.map(|c| srgb_to_linear(c) * sdr_white);
+    match encoding {
+        // Colors outside of Rec.2020 can't be stored
+        Encoding::Pq => mul(REC709_TO_REC2020, nits).map(|c| pq_from_nits(c.max(0.0))),
+        Encoding::Hlg => hlg_from_nits(mul(REC709_TO_REC2020, nits).map(|c| c.max(0.0))),
+        Encoding::ScRgb => nits.map(|c| c / SCRGB_WHITE),
+    }
+}
+
+/// The color of channels in `encoding`, where an RGB value of 1 is `sdr_white`
+fn decode(encoding: Encoding, values: [f32; 3], sdr_white: u32) -> [f32; 3] {
+    let sdr_white = as_nits(sdr_white);
+    let nits = match encoding {
+        Encoding::Pq => mul(REC2020_TO_REC709, values.map(pq_to_nits)),
+        Encoding::Hlg => mul(REC2020_TO_REC709, hlg_to_nits(values)),
+        Encoding::ScRgb => values.map(|c| c * SCRGB_WHITE),
+    };
+
+    nits.map(|c| srgb_from_linear(c / sdr_white))
+}
+
+// https://www.itu.int/rec/R-REC-BT.2100
+const PQ_M1: f32 = 2610.0 / 16384.0;
+const PQ_M2: f32 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f32 = 3424.0 / 4096.0;
+const PQ_C2: f32 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+/// Inverse of the PQ EOTF
+pub(super) fn pq_from_nits(nits: f32) -> f32 {
+    let y = (nits / 10000.0).clamp(0.0, 1.0).powf(PQ_M1);
+    ((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y)).powf(PQ_M2)
+}
+
+/// The PQ EOTF
+pub(super) fn pq_to_nits(signal: f32) -> f32 {
+    let e = signal.clamp(0.0, 1.0).powf(1.0 / PQ_M2);
+    let y = ((e - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * e)).powf(1.0 / PQ_M1);
+    y * 10000.0
+}
+
+const HLG_A: f32 = 0.178_832_77;
+const HLG_B: f32 = 1.0 - 4.0 * HLG_A;
+const HLG_C: f32 = 0.559_910_7;
+
+/// The HLG OETF of normalized scene light
+pub(super) fn hlg_oetf(e: f32) -> f32 {
+    if e <= 1.0 / 12.0 {
+        (3.0 * e).sqrt()
+    } else {
+        HLG_A * (12.0 * e - HLG_B).ln() + HLG_C
+    }
+}
+
+/// Inverse of the HLG OETF
+pub(super) fn hlg_inverse_oetf(signal: f32) -> f32 {
+    if signal <= 0.5 {
+        signal * signal / 3.0
+    } else {
+        (((signal - HLG_C) / HLG_A).exp() + HLG_B) / 12.0
+    }
+}
+
+/// Light in cd/m² of the reference HLG display, the HLG EOTF without black lift
+fn hlg_to_nits(signal: [f32; 3]) -> [f32; 3] {
+    let scene = signal.map(|c| hlg_inverse_oetf(c.clamp(0.0, 1.0)));
+    let y = dot(REC2020_LUMA, scene);
+    if y <= 0.0 {
+        return [0.0; 3];
+    }
+
+    let gain = HLG_PEAK * y.powf(HLG_GAMMA - 1.0);
+    scene.map(|c| c * gain)
+}
+
+/// Signal of light in cd/m² on the reference HLG display
+fn hlg_from_nits(nits: [f32; 3]) -> [f32; 3] {
+    let y = dot(REC2020_LUMA, nits);
+    if y <= 0.0 {
+        return [0.0; 3];
+    }
+
+    let scene_y = (y / HLG_PEAK).powf(1.0 / HLG_GAMMA);
+    let gain = HLG_PEAK * scene_y.powf(HLG_GAMMA - 1.0);
+    nits.map(|c| hlg_oetf((c / gain).clamp(0.0, 1.0)))
+}
+
+/// Removes the sRGB tone curve, keeping the sign of out of gamut values
+pub fn srgb_to_linear(value: f32) -> f32 {
+    let abs = value.abs();
+    let linear = if abs <= 0.04045 {
+        abs / 12.92
+    } else {
+        ((abs + 0.055) / 1.055).powf(2.4)
+    };
+
+    linear.copysign(value)
+}
+
+/// Applies the sRGB tone curve, keeping the sign of out of gamut values
+pub fn srgb_from_linear(value: f32) -> f32 {
+    let abs = value.abs();
+    let srgb = if abs <= 0.003_130_8 {
+        abs * 12.92
+    } else {
+        1.055 * abs.powf(1.0 / 2.4) - 0.055
+    };
+
+    srgb.copysign(value)
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn mul(matrix: [[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| dot(row, v))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        decode, encode, hlg_inverse_oetf, hlg_oetf, pq_from_nits, pq_to_nits, srgb_from_linear,
+        srgb_to_linear, tone_map, Encoding, Hdr, SDR_WHITE,
+    };
+    use crate::colorspace::test::{aprox_eq, aprox_eq_within};
+
+    #[test]
+    fn linear() {
+        assert!(aprox_eq(&[srgb_to_linear(0.0)], &[0.0]));
+        assert!(aprox_eq(&[srgb_to_linear(1.0)], &[1.0]));
+        assert!(aprox_eq(&[srgb_to_linear(0.5)], &[0.214_041]));
+        assert!(aprox_eq(&[srgb_to_linear(-0.5)], &[-0.214_041]));
+        assert!(aprox_eq(&[srgb_to_linear(0.02)], &[0.02 / 12.92]));
+
+        for value in [-0.5, 0.01, 0.5, 1.0, 2.0] {
+            assert!(aprox_eq(
+                &[srgb_from_linear(srgb_to_linear(value))],
+                &[value]
+            ));
+        }
+    }
+
+    /// Signals of BT.2408 and the ST 2084 tables
+    #[test]
+    fn pq() {
+        assert!(aprox_eq(&[pq_from_nits(0.0)], &[0.0]));
+        assert!(aprox_eq_within(&[pq_from_nits(100.0)], &[0.508], 1e-3));
+        assert!(aprox_eq_within(&[pq_from_nits(203.0)], &[0.581], 1e-3));
+        assert!(aprox_eq_within(&[pq_from_nits(1000.0)], &[0.752], 1e-3));
+        assert!(aprox_eq(&[pq_from_nits(10000.0)], &[1.0]));
+
+        for nits in [0.1, 1.0, 80.0, 203.0, 4000.0] {
+            assert!(aprox_eq_within(
+                &[pq_to_nits(pq_from_nits(nits))],
+                &[nits],
+                nits * 1e-3
+            ));
+        }
+    }
+
+    #[test]
+    fn hlg() {
+        assert!(aprox_eq(&[hlg_oetf(1.0 / 12.0)], &[0.5]));
+        assert!(aprox_eq(&[hlg_oetf(1.0)], &[1.0]));
+
+        for e in [0.0, 0.01, 1.0 / 12.0, 0.26, 1.0] {
+            assert!(aprox_eq(&[hlg_inverse_oetf(hlg_oetf(e))], &[e]));
+        }
+    }
+
+    /// BT.2408 puts the SDR white at 203 cd/m², 58% PQ and 75% HLG
+    #[test]
+    fn sdr_white() {
+        let pq = encode(Encoding::Pq, [1.0; 3], SDR_WHITE);
+        assert!(aprox_eq_within(&pq, &[0.581; 3], 1e-3), "PQ {pq:?}");
+        let hlg = encode(Encoding::Hlg, [1.0; 3], SDR_WHITE);
+        assert!(aprox_eq_within(&hlg, &[0.75; 3], 1e-3), "HLG {hlg:?}");
+        let scrgb = encode(Encoding::ScRgb, [1.0; 3], SDR_WHITE);
+        assert!(aprox_eq(&scrgb, &[203.0 / 80.0; 3]), "scRGB {scrgb:?}");
+    }
+
+    #[test]
+    fn extended_range() {
+        // Brighter than the SDR white and outside of sRGB
+        for rgb in [[2.0, 1.5, 1.2], [1.1, -0.1, 0.2], [-0.2, 0.9, 1.3]] {
+            let scrgb = encode(Encoding::ScRgb, rgb, SDR_WHITE);
+            assert!(
+                aprox_eq(&decode(Encoding::ScRgb, scrgb, SDR_WHITE), &rgb),
+                "{rgb:?}"
+            );
+        }
+
+        let rgb = [2.0, 1.5, 1.2];
+        for encoding in [Encoding::Pq, Encoding::Hlg] {
+            let values = encode(encoding, rgb, SDR_WHITE);
+            assert!(values.iter().all(|c| (0.0..=1.0).contains(c)));
+            assert!(
+                aprox_eq_within(&decode(encoding, values, SDR_WHITE), &rgb, 1e-3),
+                "{encoding:?} of {rgb:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn tone_mapping() {
+        assert!(aprox_eq(&tone_map([0.0; 3], SDR_WHITE, 1000), &[0.0; 3]));
+
+        // The peak is the display's white
+        let peak = srgb_from_linear(1000.0 / 203.0);
+        assert!(aprox_eq_within(
+            &tone_map([peak; 3], SDR_WHITE, 1000),
+            &[1.0; 3],
+            1e-3
+        ));
+
+        // Brighter colors stay brighter
+        let dim = tone_map([1.0; 3], SDR_WHITE, 1000)[0];
+        let bright = tone_map([1.5; 3], SDR_WHITE, 1000)[0];
+        assert!(dim < bright && bright < 1.0);
+    }
+
+    #[test]
+    fn change_sdr_white() {
+        let rgb = [0.8, 0.5, 0.2];
+        let mut hdr = Hdr::from_rgb(rgb, SDR_WHITE);
+        let values = hdr.values;
+
+        // A brighter SDR white stores the same color with brighter channels
+        hdr.set_sdr_white(400);
+        assert!(aprox_eq_within(&hdr.to_rgb(), &rgb, 1e-3));
+        assert!(hdr.values.iter().zip(values).all(|(new, old)| *new > old));
+        assert!(aprox_eq_within(
+            &Hdr::from_rgb_with(rgb, Encoding::Pq, 400).values,
+            &hdr.values,
+            1e-4
+        ));
+    }
+}
@@ -1,12 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::{
-    iced::{gradient::ColorStop, Alignment, Color},
+    iced::{gradient::ColorStop, Color},
     widget,
 };
 
+use super::{Channel, Context, Field};
 use crate::{
-    colorspace::ColorSpaceMessage as Message, fl, shaders::hsv as shader, widgets::color_slider,
+    colorspace::ColorSpaceMessage as Message,
+    fl,
+    shaders::{hsv as shader, Preview},
 };
 
 const COLOR_STOPS_HUE: [ColorStop; 7] = [
@@ -63,19 +66,41 @@ const COLOR_STOPS_VALUE: [ColorStop; 2] = [
 #[derive(Clone)]
 pub struct Hsv {
     pub values: [f32; 3],
-    pub strings: [String; 3],
+    /// Text fields of the channels
+    pub fields: [Field; 3],
 }
 
 impl Hsv {
+    pub const CHANNELS: [Channel; 3] = [
+        Channel::angle(),
+        Channel::new(0.0, 1.0),
+        Channel::new(0.0, 1.0),
+    ];
+
     pub fn from_rgb(rgb: [f32; 3]) -> Self {
         let hsv = rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
 
         Self {
-            strings: [hsv[0].to_string(), hsv[1].to_string(), hsv[2].to_string()],
             values: hsv,
+            fields: Default::default(),
         }
     }
 
+    /// Converts from `rgb` while keeping the current hue and saturation
+    /// when the new color leaves them undefined
+    pub fn sync_from_rgb(&mut self, rgb: [f32; 3]) {
+        let mut hsv = rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
+        if hsv[2] <= super::ACHROMATIC {
+            hsv[0] = self.values[0];
+            hsv[1] = self.values[1];
+        } else if hsv[1] <= super::ACHROMATIC {
+            hsv[0] = self.values[0];
+        }
+
+        self.fields = Default::default();
+        self.values = hsv;
+    }
+
     pub fn to_rgb(&self) -> [f32; 3] {
         hsv_to_rgb(self.values[0], self.values[1], self.values[2])
     }
@@ -86,88 +111,29 @@ impl Hsv {
 }
 
 impl Hsv {
-    pub fn change_value(&mut self, index: usize, value: f32) {
-        self.values[index] = value;
-        self.strings[index] = value.to_string();
-    }
-
-    pub fn change_string(&mut self, index: usize, string: String) {
-        if let Ok(value) = string.parse::<f32>() {
-            self.values[index] = value;
+    pub fn view<'a>(&self, ctx: &Context, show_graphs: bool) -> cosmic::Element<'a, Message> {
+        let names = [fl!("hue"), fl!("saturation"), fl!("value")];
+        let stops: [&'static [ColorStop]; 3] = [
+            &COLOR_STOPS_HUE,
+            &COLOR_STOPS_SATURATION,
+            &COLOR_STOPS_VALUE,
+        ];
+
+        let mut content = widget::column::with_capacity(3).spacing(10.0);
+        for (index, (name, stops)) in names.into_iter().zip(stops).enumerate() {
+            let card = Self::CHANNELS[index].view(
+                ctx,
+                index,
+                name,
+                self.values[index],
+                &self.fields[index],
+                stops,
+            );
+            content = content.push(widget::container(card).class(cosmic::style::Container::Card));
         }
 
-        self.strings[index] = string;
-    }
-
-    pub fn view<'a>(&self, show_graphs: bool) -> cosmic::Element<'a, Message> {
-        let values = &self.values;
-        let strings = &self.strings;
-
-        let red = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("hue")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[0].clone())
-                            .on_input(|string| Message::ChangeString { index: 0, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=360f32,
-                values[0],
-                |value| Message::ChangeValue { index: 0, value },
-                &COLOR_STOPS_HUE,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let green = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("saturation")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[1].clone())
-                            .on_input(|string| Message::ChangeString { index: 1, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[1],
-                |value| Message::ChangeValue { index: 1, value },
-                &COLOR_STOPS_SATURATION,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let blue = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("value")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[2].clone())
-                            .on_input(|string| Message::ChangeString { index: 2, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[2],
-                |value| Message::ChangeValue { index: 2, value },
-                &COLOR_STOPS_VALUE,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-
-        let mut content = widget::column::with_capacity(3)
-            .push(widget::container(red).class(cosmic::style::Container::Card))
-            .push(widget::container(green).class(cosmic::style::Container::Card))
-            .push(widget::container(blue).class(cosmic::style::Container::Card))
-            .spacing(10.0);
-
         if show_graphs {
+            let preview = Preview::new(ctx);
             content = content.push(
                 widget::container(
                     widget::container(
@@ -175,6 +141,7 @@ impl Hsv {
                             hue: self.values[0],
                             saturation: self.values[1],
                             value: self.values[2],
+                            preview,
                         })
                         .width(100)
                         .height(100),
@@ -190,7 +157,7 @@ impl Hsv {
 }
 
 #[allow(clippy::many_single_char_names)]
-fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [f32; 3] {
+pub(super) fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [f32; 3] {
     let c = v * s;
     let h_ = h / 60.0;
     let x = c * (1.0 - (h_ % 2.0 - 1.0).abs());
@@ -215,7 +182,7 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [f32; 3] {
 }
 
 #[allow(clippy::many_single_char_names, clippy::float_cmp)]
-fn rgb_to_hsv(r: f32, g: f32, b: f32) -> [f32; 3] {
+pub(super) fn rgb_to_hsv(r: f32, g: f32, b: f32) -> [f32; 3] {
     let x_max = r.max(g).max(b);
     let x_min = r.min(g).min(b);
     let c = x_max - x_min;
@@ -235,6 +202,10 @@ fn rgb_to_hsv(r: f32, g: f32, b: f32) -> [f32; 3] {
     if h < 0.0 {
         h += 360.0;
     }
+    // A tiny negative hue rounds up to a full turn
+    if h >= 360.0 {
+        h = 0.0;
+    }
 
     let s = if x_max == 0.0 { 0.0 } else { c / x_max };
 
@@ -244,7 +215,8 @@ fn rgb_to_hsv(r: f32, g: f32, b: f32) -> [f32; 3] {
 // ---- Tests ----
 #[cfg(test)]
 mod test {
-    use super::{hsv_to_rgb, rgb_to_hsv};
+    use super::{hsv_to_rgb, rgb_to_hsv, Hsv};
+    use crate::colorspace::test::aprox_eq;
 
     #[test]
     fn white() {
@@ -296,14 +268,17 @@ mod test {
         assert!(aprox_eq(&rgb, &[0f32, 0f32, 1f32]));
     }
 
-    fn aprox_eq(a: &[f32; 3], b: &[f32; 3]) -> bool {
-        const EPSILON: f32 = 1e-4;
+    #[test]
+    fn sync_keeps_hue() {
+        let mut hsv = Hsv::from_rgb([0.0, 1.0, 0.0]);
+
+        hsv.sync_from_rgb([0.5; 3]);
+        assert!(aprox_eq(&hsv.values, &[120.0, 0.0, 0.5]));
+
+        hsv.sync_from_rgb([0.0; 3]);
+        assert!(aprox_eq(&hsv.values, &[120.0, 0.0, 0.0]));
 
-        a[0] > b[0] - EPSILON
-            && a[0] < b[0] + EPSILON
-            && a[1] > b[1] - EPSILON
-            && a[1] < b[1] + EPSILON
-            && a[2] > b[2] - EPSILON
-            && a[2] < b[2] + EPSILON
+        hsv.sync_from_rgb([0.0, 0.0, 1.0]);
+        assert!(aprox_eq(&hsv.values, &[240.0, 1.0, 1.0]));
     }
 }
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+use std::path::Path;
+
+use lcms2::{ColorSpaceSignature, InfoType, Locale, PixelFormat, Profile, Transform};
+use serde::{Deserialize, Serialize};
+
+use crate::fl;
+
+/// How colors outside of the profile's gamut are mapped into it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Intent {
+    #[default]
+    Perceptual,
+    RelativeColorimetric,
+    Saturation,
+    AbsoluteColorimetric,
+}
+
+impl Intent {
+    pub const ALL: [Intent; 4] = [
+        Intent::Perceptual,
+        Intent::RelativeColorimetric,
+        Intent::Saturation,
+        Intent::AbsoluteColorimetric,
+    ];
+
+    fn lcms(self) -> lcms2::Intent {
+        match self {
+            Intent::Perceptual => lcms2::Intent::Perceptual,
+            Intent::RelativeColorimetric => lcms2::Intent::RelativeColorimetric,
+            Intent::Saturation => lcms2::Intent::Saturation,
+            Intent::AbsoluteColorimetric => lcms2::Intent::AbsoluteColorimetric,
+        }
+    }
+}
+
+impl Display for Intent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Intent::Perceptual => f.write_str(&fl!("intent-perceptual")),
+            Intent::RelativeColorimetric => f.write_str(&fl!("intent-relative")),
+            Intent::Saturation => f.write_str(&fl!("intent-saturation")),
+            Intent::AbsoluteColorimetric => f.write_str(&fl!("intent-absolute")),
+        }
+    }
+}
+
+/// Converts between sRGB and the CMYK of a printing condition, e.g. FOGRA39 or GRACoL
+pub struct CmykProfile {
+    /// Description stored in the profile
+    pub name: String,
+    to_cmyk: Transform<[f32; 3], [f32; 4]>,
+    to_rgb: Transform<[f32; 4], [f32; 3]>,
+}
+
+impl CmykProfile {
+    pub fn load(path: &Path, intent: Intent) -> Option<Self> {
+        let profile = match Profile::new_file(path) {
+            Ok(profile) => profile,
+            Err(e) => {
+                log::error!("Failed to load ICC profile {path:?}: {e}");
+                return None;
+            }
+        };
+        if profile.color_space() != ColorSpaceSignature::CmykData {
+            log::error!("{path:?} is not a CMYK profile");
+            return None;
+        }
+
+        let srgb = Profile::new_srgb();
+        let to_cmyk = Transform::new(
+            &srgb,
+            PixelFormat::RGB_FLT,
+            &profile,
+            PixelFormat::CMYK_FLT,
+            intent.lcms(),
+        );
+        let to_rgb = Transform::new(
+            &profile,
+            PixelFormat::CMYK_FLT,
+            &srgb,
+            PixelFormat::RGB_FLT,
+            intent.lcms(),
+        );
+        let (to_cmyk, to_rgb) = match (to_cmyk, to_rgb) {
+            (Ok(to_cmyk), Ok(to_rgb)) => (to_cmyk, to_rgb),
+            (Err(e), _) | (_, Err(e)) => {
+                log::error!("Failed to create transforms for {path:?}: {e}");
+                return None;
+            }
+        };
+
+        let name = profile
+            .info(InfoType::Description, Locale::none())
+            .or_else(|| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+
+        Some(Self {
+            name,
+            to_cmyk,
+            to_rgb,
+        })
+    }
+
+    /// Inks of sRGB `rgb` in the printing condition
+    pub(super) fn rgb_to_cmyk(&self, rgb: [f32; 3]) -> [f32; 4] {
+        // Floating point CMYK is in percent in Little CMS
+        let mut cmyk = [[0.0; 4]];
+        self.to_cmyk.transform_pixels(&[rgb], &mut cmyk);
+        cmyk[0].map(|ink| (ink / 100.0).clamp(0.0, 1.0))
+    }
+
+    /// sRGB of inks in the printing condition
+    pub(super) fn cmyk_to_rgb(&self, cmyk: [f32; 4]) -> [f32; 3] {
+        let mut rgb = [[0.0; 3]];
+        self.to_rgb
+            .transform_pixels(&[cmyk.map(|ink| ink * 100.0)], &mut rgb);
+        rgb[0]
+    }
+}
@@ -1,12 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::{
-    iced::{gradient::ColorStop, Alignment, Color, Length},
+    iced::{gradient::ColorStop, Color, Length},
     widget,
 };
 
+use super::{Channel, Context, Field};
 use crate::{
-    colorspace::ColorSpaceMessage as Message, fl, shaders::oklab as shader, widgets::color_slider,
+    colorspace::ColorSpaceMessage as Message,
+    fl,
+    shaders::{oklab as shader, Preview},
 };
 
 const COLOR_STOPS_LIGHTNESS: [ColorStop; 2] = [
@@ -43,16 +46,23 @@ const COLOR_STOPS_BLUE_YELLOW: [ColorStop; 2] = [
 #[derive(Clone)]
 pub struct Oklab {
     pub values: [f32; 3],
-    pub strings: [String; 3],
+    /// Text fields of the channels
+    pub fields: [Field; 3],
 }
 
 impl Oklab {
+    pub const CHANNELS: [Channel; 3] = [
+        Channel::new(0.0, 1.0),
+        Channel::new(-0.5, 0.5),
+        Channel::new(-0.5, 0.5),
+    ];
+
     pub fn from_rgb(rgb: [f32; 3]) -> Self {
         let lab = rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
 
         Self {
-            strings: [lab[0].to_string(), lab[1].to_string(), lab[2].to_string()],
             values: lab,
+            fields: Default::default(),
         }
     }
 
@@ -66,87 +76,29 @@ impl Oklab {
 }
 
 impl Oklab {
-    pub fn change_value(&mut self, index: usize, value: f32) {
-        self.values[index] = value;
-        self.strings[index] = value.to_string();
-    }
-
-    pub fn change_string(&mut self, index: usize, string: String) {
-        if let Ok(value) = string.parse::<f32>() {
-            self.values[index] = value;
-        }
-
-        self.strings[index] = string;
-    }
-
-    pub fn view<'a>(&self, show_graphs: bool) -> cosmic::Element<'a, Message> {
-        let values = &self.values;
-        let strings = &self.strings;
-
-        let mut lightness = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("lightness")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[0].clone())
-                            .on_input(|string| Message::ChangeString { index: 0, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[0],
-                |value| Message::ChangeValue { index: 0, value },
-                &COLOR_STOPS_LIGHTNESS,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let mut green_red = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("green-red")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[1].clone())
-                            .on_input(|string| Message::ChangeString { index: 1, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                -0.5..=0.5,
-                values[1],
-                |value| Message::ChangeValue { index: 1, value },
-                &COLOR_STOPS_GREEN_RED,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let mut blue_yellow = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("blue-yellow")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[2].clone())
-                            .on_input(|string| Message::ChangeString { index: 2, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
+    pub fn view<'a>(&self, ctx: &Context, show_graphs: bool) -> cosmic::Element<'a, Message> {
+        let card = |index: usize, name: String, stops: &'static [ColorStop]| {
+            Self::CHANNELS[index].view(
+                ctx,
+                index,
+                name,
+                self.values[index],
+                &self.fields[index],
+                stops,
             )
-            .push(color_slider(
-                -0.5..=0.5,
-                values[2],
-                |value| Message::ChangeValue { index: 2, value },
-                &COLOR_STOPS_BLUE_YELLOW,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
+        };
+        let mut lightness = card(0, fl!("lightness"), &COLOR_STOPS_LIGHTNESS);
+        let mut green_red = card(1, fl!("green-red"), &COLOR_STOPS_GREEN_RED);
+        let mut blue_yellow = card(2, fl!("blue-yellow"), &COLOR_STOPS_BLUE_YELLOW);
 
         if show_graphs {
+            let preview = Preview::new(ctx);
             lightness = lightness.push(
                 cosmic::iced::widget::shader(shader::ColorGraph::<0> {
                     lightness: self.values[0],
                     green_red: self.values[1],
                     blue_yellow: self.values[2],
+                    preview,
                 })
                 .width(Length::Fill),
             );
@@ -155,6 +107,7 @@ impl Oklab {
                     lightness: self.values[0],
                     green_red: self.values[1],
                     blue_yellow: self.values[2],
+                    preview,
                 })
                 .width(Length::Fill),
             );
@@ -163,6 +116,7 @@ impl Oklab {
                     lightness: self.values[0],
                     green_red: self.values[1],
                     blue_yellow: self.values[2],
+                    preview,
                 })
                 .width(Length::Fill),
             );
@@ -225,6 +179,7 @@ pub fn rgb_to_oklab(r: f32, g: f32, b: f32) -> [f32; 3] {
 #[cfg(test)]
 mod test {
     use super::{oklab_to_rgb, rgb_to_oklab};
+    use crate::colorspace::test::aprox_eq;
 
     #[test]
     fn white() {
@@ -275,15 +230,4 @@ mod test {
         let rgb = oklab_to_rgb(lab[0], lab[1], lab[2]);
         assert!(aprox_eq(&rgb, &[0f32, 0f32, 1f32]));
     }
-
-    fn aprox_eq(a: &[f32; 3], b: &[f32; 3]) -> bool {
-        const EPSILON: f32 = 1e-4;
-
-        a[0] > b[0] - EPSILON
-            && a[0] < b[0] + EPSILON
-            && a[1] > b[1] - EPSILON
-            && a[1] < b[1] + EPSILON
-            && a[2] > b[2] - EPSILON
-            && a[2] < b[2] + EPSILON
-    }
 }
@@ -1,12 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::{
-    iced::{gradient::ColorStop, Alignment, Color, Length},
+    iced::{gradient::ColorStop, Color, Length},
     widget,
 };
 
+use super::{Channel, Context, Field};
 use crate::{
-    colorspace::ColorSpaceMessage as Message, fl, shaders::oklch as shader, widgets::color_slider,
+    colorspace::ColorSpaceMessage as Message,
+    fl,
+    shaders::{oklch as shader, Preview},
 };
 
 const COLOR_STOPS_LIGHTNESS: [ColorStop; 2] = [
@@ -63,19 +66,38 @@ const COLOR_STOPS_HUE: [ColorStop; 7] = [
 #[derive(Clone)]
 pub struct Oklch {
     pub values: [f32; 3],
-    pub strings: [String; 3],
+    /// Text fields of the channels
+    pub fields: [Field; 3],
 }
 
 impl Oklch {
+    pub const CHANNELS: [Channel; 3] = [
+        Channel::new(0.0, 1.0),
+        Channel::new(0.0, 0.37),
+        Channel::angle(),
+    ];
+
     pub fn from_rgb(rgb: [f32; 3]) -> Self {
         let lch = rgb_to_oklch(rgb[0], rgb[1], rgb[2]);
 
         Self {
-            strings: [lch[0].to_string(), lch[1].to_string(), lch[2].to_string()],
             values: lch,
+            fields: Default::default(),
         }
     }
 
+    /// Converts from `rgb` while keeping the current hue
+    /// when the new color has no chroma
+    pub fn sync_from_rgb(&mut self, rgb: [f32; 3]) {
+        let mut lch = rgb_to_oklch(rgb[0], rgb[1], rgb[2]);
+        if lch[1] <= super::ACHROMATIC {
+            lch[2] = self.values[2];
+        }
+
+        self.fields = Default::default();
+        self.values = lch;
+    }
+
     pub fn to_rgb(&self) -> [f32; 3] {
         oklch_to_rgb(self.values[0], self.values[1], self.values[2])
     }
@@ -86,87 +108,29 @@ impl Oklch {
 }
 
 impl Oklch {
-    pub fn change_value(&mut self, index: usize, value: f32) {
-        self.values[index] = value;
-        self.strings[index] = value.to_string();
-    }
-
-    pub fn change_string(&mut self, index: usize, string: String) {
-        if let Ok(value) = string.parse::<f32>() {
-            self.values[index] = value;
-        }
-
-        self.strings[index] = string;
-    }
-
-    pub fn view<'a>(&self, show_graphs: bool) -> cosmic::Element<'a, Message> {
-        let values = &self.values;
-        let strings = &self.strings;
-
-        let mut lightness = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("lightness")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[0].clone())
-                            .on_input(|string| Message::ChangeString { index: 0, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1.0f32,
-                values[0],
-                |value| Message::ChangeValue { index: 0, value },
-                &COLOR_STOPS_LIGHTNESS,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let mut chroma = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("chroma")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[1].clone())
-                            .on_input(|string| Message::ChangeString { index: 1, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
+    pub fn view<'a>(&self, ctx: &Context, show_graphs: bool) -> cosmic::Element<'a, Message> {
+        let card = |index: usize, name: String, stops: &'static [ColorStop]| {
+            Self::CHANNELS[index].view(
+                ctx,
+                index,
+                name,
+                self.values[index],
+                &self.fields[index],
+                stops,
             )
-            .push(color_slider(
-                0f32..=0.37f32,
-                values[1],
-                |value| Message::ChangeValue { index: 1, value },
-                &COLOR_STOPS_CHROMA,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let mut hue = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("hue")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[2].clone())
-                            .on_input(|string| Message::ChangeString { index: 2, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=360f32,
-                values[2],
-                |value| Message::ChangeValue { index: 2, value },
-                &COLOR_STOPS_HUE,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
+        };
+        let mut lightness = card(0, fl!("lightness"), &COLOR_STOPS_LIGHTNESS);
+        let mut chroma = card(1, fl!("chroma"), &COLOR_STOPS_CHROMA);
+        let mut hue = card(2, fl!("hue"), &COLOR_STOPS_HUE);
 
         if show_graphs {
+            let preview = Preview::new(ctx);
             lightness = lightness.push(
                 cosmic::iced::widget::shader(shader::ColorGraph::<0> {
                     lightness: self.values[0],
                     chroma: self.values[1],
                     hue: self.values[2],
+                    preview,
                 })
                 .width(Length::Fill),
             );
@@ -175,6 +139,7 @@ impl Oklch {
                     lightness: self.values[0],
                     chroma: self.values[1],
                     hue: self.values[2],
+                    preview,
                 })
                 .width(Length::Fill),
             );
@@ -183,6 +148,7 @@ impl Oklch {
                     lightness: self.values[0],
                     chroma: self.values[1],
                     hue: self.values[2],
+                    preview,
                 })
                 .width(Length::Fill),
             );
@@ -199,7 +165,7 @@ impl Oklch {
 }
 
 #[allow(clippy::many_single_char_names)]
-fn oklch_to_rgb(l: f32, c: f32, h: f32) -> [f32; 3] {
+pub(super) fn oklch_to_rgb(l: f32, c: f32, h: f32) -> [f32; 3] {
     let h = h.to_radians();
     let a = c * h.cos();
     let b = c * h.sin();
@@ -208,7 +174,7 @@ fn oklch_to_rgb(l: f32, c: f32, h: f32) -> [f32; 3] {
 }
 
 #[allow(clippy::many_single_char_names)]
-fn rgb_to_oklch(r: f32, g: f32, b: f32) -> [f32; 3] {
+pub(super) fn rgb_to_oklch(r: f32, g: f32, b: f32) -> [f32; 3] {
     let lab = super::oklab::rgb_to_oklab(r, g, b);
     let mut lch = [
         lab[0],
@@ -219,13 +185,18 @@ fn rgb_to_oklch(r: f32, g: f32, b: f32) -> [f32; 3] {
     if lch[2] < 0.0 {
         lch[2] += 360.0;
     }
+    // A tiny negative hue rounds up to a full turn
+    if lch[2] >= 360.0 {
+        lch[2] = 0.0;
+    }
 
     lch
 }
 
 #[cfg(test)]
 mod test {
-    use super::{oklch_to_rgb, rgb_to_oklch};
+    use super::{oklch_to_rgb, rgb_to_oklch, Oklch};
+    use crate::colorspace::test::aprox_eq;
 
     #[test]
     fn white() {
@@ -277,14 +248,16 @@ mod test {
         assert!(aprox_eq(&rgb, &[0f32, 0f32, 1f32]));
     }
 
-    fn aprox_eq(a: &[f32; 3], b: &[f32; 3]) -> bool {
-        const EPSILON: f32 = 1e-4;
+    #[test]
+    fn sync_keeps_hue() {
+        let mut lch = Oklch::from_rgb([0.0, 0.0, 1.0]);
+        let hue = lch.values[2];
+
+        lch.sync_from_rgb([0.5; 3]);
+        assert!(lch.values[1] < 1e-4);
+        assert!((lch.values[2] - hue).abs() < 1e-4);
 
-        a[0] > b[0] - EPSILON
-            && a[0] < b[0] + EPSILON
-            && a[1] > b[1] - EPSILON
-            && a[1] < b[1] + EPSILON
-            && a[2] > b[2] - EPSILON
-            && a[2] < b[2] + EPSILON
+        lch.sync_from_rgb([1.0, 0.0, 0.0]);
+        assert!(aprox_eq(&lch.values, &rgb_to_oklch(1.0, 0.0, 0.0)));
     }
 }
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+
+use super::{ColorSpace, ColorSpaceCombo, Conversion, Oklch};
+use crate::fl;
+
+/// Step used by lighten/darken, in OKLCH lightness
+const LIGHTNESS_STEP: f32 = 0.05;
+/// Step used by saturate/desaturate, in OKLCH chroma
+const CHROMA_STEP: f32 = 0.02;
+/// Step used by hue rotation, in degrees
+const HUE_STEP: f32 = 30.0;
+
+/// CSS-like relative color adjustments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Lighten,
+    Darken,
+    Saturate,
+    Desaturate,
+    RotateHue,
+    RotateHueBack,
+    Invert,
+    Grayscale,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 8] = [
+        Operation::Lighten,
+        Operation::Darken,
+        Operation::Saturate,
+        Operation::Desaturate,
+        Operation::RotateHue,
+        Operation::RotateHueBack,
+        Operation::Invert,
+        Operation::Grayscale,
+    ];
+
+    pub fn apply(self, rgb: [f32; 3]) -> [f32; 3] {
+        let mut lch = Oklch::from_rgb(rgb);
+        let [l, c, h] = lch.values;
+        let (index, value) = match self {
+            Operation::Lighten => (0, (l + LIGHTNESS_STEP).min(1.0)),
+            Operation::Darken => (0, (l - LIGHTNESS_STEP).max(0.0)),
+            Operation::Saturate => (1, c + CHROMA_STEP),
+            Operation::Desaturate => (1, (c - CHROMA_STEP).max(0.0)),
+            Operation::RotateHue => (2, (h + HUE_STEP).rem_euclid(360.0)),
+            Operation::RotateHueBack => (2, (h - HUE_STEP).rem_euclid(360.0)),
+            Operation::Grayscale => (1, 0.0),
+            // Inverted in RGB, not OKLCH
+            Operation::Invert => return rgb.map(|c| 1.0 - c),
+        };
+
+        lch.values[index] = value;
+        lch.to_rgb()
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Lighten => f.write_str(&fl!("op-lighten")),
+            Operation::Darken => f.write_str(&fl!("op-darken")),
+            Operation::Saturate => f.write_str(&fl!("op-saturate")),
+            Operation::Desaturate => f.write_str(&fl!("op-desaturate")),
+            Operation::RotateHue => f.write_str(&fl!("op-rotate-hue", degrees = HUE_STEP)),
+            Operation::RotateHueBack => f.write_str(&fl!("op-rotate-hue", degrees = -HUE_STEP)),
+            Operation::Invert => f.write_str(&fl!("op-invert")),
+            Operation::Grayscale => f.write_str(&fl!("op-grayscale")),
+        }
+    }
+}
+
+/// Mixes `amount` (0-1) of `b` into `a` by interpolating in `space`,
+/// like CSS `color-mix()`
+pub fn mix(
+    a: [f32; 3],
+    b: [f32; 3],
+    amount: f32,
+    space: ColorSpaceCombo,
+    conversion: &Conversion,
+) -> [f32; 3] {
+    let mut mixed = ColorSpace::from_combo(space, a, conversion);
+    let other = ColorSpace::from_combo(space, b, conversion);
+    let hue = match space {
+        ColorSpaceCombo::Hsv => Some(0),
+        ColorSpaceCombo::Oklch => Some(2),
+        _ => None,
+    };
+
+    let channels: Vec<(f32, f32)> = mixed
+        .values()
+        .iter()
+        .copied()
+        .zip(other.values().iter().copied())
+        .collect();
+    for (index, (from, to)) in channels.into_iter().enumerate() {
+        let value = if hue == Some(index) {
+            // Shorter arc
+            let delta = (to - from + 180.0).rem_euclid(360.0) - 180.0;
+            (from + delta * amount).rem_euclid(360.0)
+        } else {
+            from + (to - from) * amount
+        };
+
+        mixed.change_value(index, value);
+    }
+
+    mixed.get_rgb()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{mix, Operation};
+    use crate::colorspace::{test::aprox_eq_within, ColorSpaceCombo, Conversion, Oklch};
+
+    #[test]
+    fn invert() {
+        let rgb = Operation::Invert.apply([1.0, 0.25, 0.0]);
+        assert!(aprox_eq_within(&rgb, &[0.0, 0.75, 1.0], 1e-3));
+    }
+
+    #[test]
+    fn grayscale() {
+        let rgb = Operation::Grayscale.apply([1.0, 0.0, 0.0]);
+        assert!(aprox_eq_within(&[rgb[0], rgb[1]], &[rgb[1], rgb[2]], 1e-3));
+    }
+
+    #[test]
+    fn lighten_darken() {
+        let gray = [0.5; 3];
+        let lighter = Oklch::from_rgb(Operation::Lighten.apply(gray));
+        let darker = Oklch::from_rgb(Operation::Darken.apply(gray));
+        let l = Oklch::from_rgb(gray).values[0];
+
+        assert!((lighter.values[0] - l - 0.05).abs() < 1e-3);
+        assert!((l - darker.values[0] - 0.05).abs() < 1e-3);
+    }
+
+    #[test]
+    fn rotate_hue() {
+        let red = [1.0, 0.0, 0.0];
+        let h = Oklch::from_rgb(red).values[2];
+        let rotated = Oklch::from_rgb(Operation::RotateHue.apply(red)).values[2];
+
+        assert!(((rotated - h).rem_euclid(360.0) - 30.0).abs() < 1e-2);
+        let back = Operation::RotateHueBack.apply(Operation::RotateHue.apply(red));
+        assert!(aprox_eq_within(&back, &red, 1e-3));
+    }
+
+    #[test]
+    fn mix_rgb() {
+        let rgb = mix(
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0],
+            0.25,
+            ColorSpaceCombo::Rgb,
+            &Conversion::default(),
+        );
+        assert!(aprox_eq_within(&rgb, &[0.75, 0.0, 0.25], 1e-3));
+    }
+
+    #[test]
+    fn mix_shorter_hue_arc() {
+        // Red (0°) and magenta (300°) should mix through 330°, not through green
+        let rgb = mix(
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 1.0],
+            0.5,
+            ColorSpaceCombo::Hsv,
+            &Conversion::default(),
+        );
+        assert!(aprox_eq_within(&rgb, &[1.0, 0.0, 0.5], 1e-3));
+    }
+
+    #[test]
+    fn mix_endpoints() {
+        let a = [0.2, 0.4, 0.6];
+        let b = [0.9, 0.1, 0.3];
+        for space in [
+            ColorSpaceCombo::Rgb,
+            ColorSpaceCombo::Hsv,
+            ColorSpaceCombo::Oklab,
+            ColorSpaceCombo::Oklch,
+        ] {
+            let conversion = Conversion::default();
+            assert!(aprox_eq_within(
+                &mix(a, b, 0.0, space, &conversion),
+                &a,
+                1e-3
+            ));
+            assert!(aprox_eq_within(
+                &mix(a, b, 1.0, space, &conversion),
+                &b,
+                1e-3
+            ));
+        }
+    }
+}
@@ -1,11 +1,12 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::{
-    iced::{gradient::ColorStop, Alignment, Color},
+    iced::{gradient::ColorStop, Color},
     widget,
 };
 
-use crate::{colorspace::ColorSpaceMessage as Message, fl, widgets::color_slider};
+use super::{Channel, Context, Field, Unit};
+use crate::{colorspace::ColorSpaceMessage as Message, fl};
 
 const COLOR_STOPS_RED: [ColorStop; 2] = [
     ColorStop {
@@ -41,23 +42,34 @@ const COLOR_STOPS_BLUE: [ColorStop; 2] = [
 #[derive(Clone)]
 pub struct Rgb {
     pub values: [f32; 3],
-    pub strings: [String; 3],
+    /// Text fields of the channels
+    pub fields: [Field; 3],
+    /// Unit of the channels, `None` follows the global unit
+    pub unit: Option<Unit>,
 }
 
 impl Default for Rgb {
     fn default() -> Self {
         Self {
             values: [1.0; 3],
-            strings: ["1".into(), "1".into(), "1".into()],
+            fields: Default::default(),
+            unit: None,
         }
     }
 }
 
 impl Rgb {
+    pub const CHANNELS: [Channel; 3] = [
+        Channel::new(0.0, 1.0).open(),
+        Channel::new(0.0, 1.0).open(),
+        Channel::new(0.0, 1.0).open(),
+    ];
+
     pub fn from_rgb(rgb: [f32; 3]) -> Self {
         Self {
-            strings: [rgb[0].to_string(), rgb[1].to_string(), rgb[2].to_string()],
             values: rgb,
+            fields: Default::default(),
+            unit: None,
         }
     }
 
@@ -71,87 +83,85 @@ impl Rgb {
 }
 
 impl Rgb {
-    pub fn change_value(&mut self, index: usize, value: f32) {
-        self.values[index] = value;
-        self.strings[index] = value.to_string();
+    /// Channels in the column's unit
+    pub fn channels(&self) -> [Channel; 3] {
+        Self::CHANNELS.map(|channel| channel.with_unit(self.unit))
+    }
+
+    /// Shows the channels in `unit`, `None` follows the global unit
+    pub fn set_unit(&mut self, unit: Option<Unit>) {
+        self.unit = unit;
+        self.fields = Default::default();
     }
 
-    pub fn change_string(&mut self, index: usize, string: String) {
-        if let Ok(value) = string.parse::<f32>() {
-            self.values[index] = value;
+    pub fn view<'a>(&self, ctx: &Context, _show_graphs: bool) -> cosmic::Element<'a, Message> {
+        let channels = self.channels();
+        let names = [fl!("red"), fl!("green"), fl!("blue")];
+        let stops = [&COLOR_STOPS_RED, &COLOR_STOPS_GREEN, &COLOR_STOPS_BLUE];
+
+        let mut content = widget::column::with_capacity(3).spacing(10.0);
+        for (index, (name, stops)) in names.into_iter().zip(stops).enumerate() {
+            let card = channels[index].view(
+                ctx,
+                index,
+                name,
+                self.values[index],
+                &self.fields[index],
+                stops,
+            );
+            content = content.push(widget::container(card).class(cosmic::style::Container::Card));
         }
 
-        self.strings[index] = string;
+        content.into()
     }
+}
 
-    pub fn view<'a>(&self, _show_graphs: bool) -> cosmic::Element<'a, Message> {
-        let values = &self.values;
-        let strings = &self.strings;
-
-        let red = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("red")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[0].clone())
-                            .on_input(|string| Message::ChangeString { index: 0, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[0],
-                |value| Message::ChangeValue { index: 0, value },
-                &COLOR_STOPS_RED,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let green = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("green")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[1].clone())
-                            .on_input(|string| Message::ChangeString { index: 1, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[1],
-                |value| Message::ChangeValue { index: 1, value },
-                &COLOR_STOPS_GREEN,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-        let blue = widget::column::with_capacity(3)
-            .push(
-                widget::row::with_capacity(2)
-                    .push(widget::text(fl!("blue")).size(20.0))
-                    .push(
-                        widget::text_input("", strings[2].clone())
-                            .on_input(|string| Message::ChangeString { index: 2, string }),
-                    )
-                    .align_y(Alignment::Center)
-                    .spacing(10.0),
-            )
-            .push(color_slider(
-                0f32..=1f32,
-                values[2],
-                |value| Message::ChangeValue { index: 2, value },
-                &COLOR_STOPS_BLUE,
-            ))
-            .spacing(10.0)
-            .padding(10.0);
-
-        let content = widget::column::with_capacity(3)
-            .push(widget::container(red).class(cosmic::style::Container::Card))
-            .push(widget::container(green).class(cosmic::style::Container::Card))
-            .push(widget::container(blue).class(cosmic::style::Container::Card))
-            .spacing(10.0);
+/// Parses 8-bit sRGB like `255, 128, 0`, `255 128 0` or `rgb(255, 128, 0)`
+pub fn parse_srgb(input: &str) -> Option<[f32; 3]> {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("rgb(")
+        .and_then(|input| input.strip_suffix(')'))
+        .unwrap_or(input);
 
-        content.into()
+    let mut channels = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|channel| !channel.is_empty())
+        .map(|channel| channel.parse::<u8>().ok().map(|c| f32::from(c) / 255.0));
+
+    let srgb = [channels.next()??, channels.next()??, channels.next()??];
+    channels.next().is_none().then_some(srgb)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_srgb, Rgb};
+    use crate::colorspace::{ColorSpace, Context};
+
+    #[test]
+    fn srgb() {
+        let orange = Some([1.0, 128.0 / 255.0, 0.0]);
+        assert_eq!(parse_srgb("255, 128, 0"), orange);
+        assert_eq!(parse_srgb(" 255 128 0 "), orange);
+        assert_eq!(parse_srgb("rgb(255,128,0)"), orange);
+    }
+
+    #[test]
+    fn srgb_invalid() {
+        assert_eq!(parse_srgb("255, 128"), None);
+        assert_eq!(parse_srgb("255, 128, 0, 0"), None);
+        assert_eq!(parse_srgb("256, 0, 0"), None);
+        assert_eq!(parse_srgb("0.5, 0, 0"), None);
+    }
+
+    #[test]
+    fn extended() {
+        let ctx = Context::default();
+        let mut space = ColorSpace::Rgb(Rgb::from_rgb([0.5; 3]));
+
+        space.change_string(&ctx, 0, "1.5".into());
+        space.change_string(&ctx, 1, "+1".into());
+        space.submit_string(&ctx, 1);
+        assert_eq!(space.get_rgb(), [1.5, 1.5, 0.5]);
     }
 }
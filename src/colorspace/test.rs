@@ -0,0 +1,300 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Conversion tests shared by all color spaces, and helpers for the tests of each space
+
+use super::{cmyk, hsv, oklab, oklch, ColorSpace, ColorSpaceCombo, Conversion};
+
+const ALL: [ColorSpaceCombo; 6] = [
+    ColorSpaceCombo::Rgb,
+    ColorSpaceCombo::Hsv,
+    ColorSpaceCombo::Oklab,
+    ColorSpaceCombo::Oklch,
+    ColorSpaceCombo::Cmyk,
+    ColorSpaceCombo::Hdr,
+];
+
+/// Number of random colors checked by each property
+const SAMPLES: usize = 2000;
+
+pub(crate) fn aprox_eq(a: &[f32], b: &[f32]) -> bool {
+    aprox_eq_within(a, b, 1e-4)
+}
+
+pub(crate) fn aprox_eq_within(a: &[f32], b: &[f32], epsilon: f32) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| *a > *b - epsilon && *a < *b + epsilon)
+}
+
+/// Deterministic pseudo-random colors, so failures can be reproduced
+pub(super) struct Sampler(u64);
+
+impl Sampler {
+    pub(super) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Uniform in `0.0..=1.0`
+    pub(super) fn next(&mut self) -> f32 {
+        // Knuth's MMIX LCG, the high bits are the random ones
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        #[allow(clippy::cast_precision_loss)]
+        let value = (self.0 >> 40) as f32 / ((1u64 << 24) - 1) as f32;
+        value
+    }
+
+    pub(super) fn rgb(&mut self) -> [f32; 3] {
+        [self.next(), self.next(), self.next()]
+    }
+}
+
+/// Random colors, then the corners and edges of the RGB cube
+fn colors() -> impl Iterator<Item = [f32; 3]> {
+    let mut sampler = Sampler::new(0x00C0_FFEE);
+    let steps = [0.0, 0.5, 1.0];
+    let grid = steps.into_iter().flat_map(move |r| {
+        steps
+            .into_iter()
+            .flat_map(move |g| steps.map(|b| [r, g, b]))
+    });
+
+    std::iter::repeat_with(move || sampler.rgb())
+        .take(SAMPLES)
+        .chain(grid)
+}
+
+/// Difference of two hues in degrees, ignoring full turns
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+#[test]
+fn round_trip_all_pairs() {
+    let conversion = Conversion::default();
+    for rgb in colors() {
+        for from in ALL {
+            for to in ALL {
+                let space = ColorSpace::from_combo(from, rgb, &conversion);
+                let converted = ColorSpace::from_combo(to, space.get_rgb(), &conversion);
+                let back = ColorSpace::from_combo(from, converted.get_rgb(), &conversion).get_rgb();
+
+                assert!(
+                    aprox_eq_within(&back, &rgb, 1e-3),
+                    "{from:?} -> {to:?} -> {from:?} of {rgb:?} gave {back:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::many_single_char_names)]
+fn round_trip_values() {
+    for rgb in colors() {
+        let [h, s, v] = hsv::rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
+        let [h2, s2, v2] = {
+            let rgb = hsv::hsv_to_rgb(h, s, v);
+            hsv::rgb_to_hsv(rgb[0], rgb[1], rgb[2])
+        };
+        assert!(aprox_eq_within(&[s2, v2], &[s, v], 1e-3), "HSV of {rgb:?}");
+        // Hue is undefined for grays
+        if s > 1e-3 && v > 1e-3 {
+            assert!(hue_distance(h, h2) < 0.1, "HSV hue of {rgb:?}");
+        }
+
+        let lab = oklab::rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
+        let lab2 = {
+            let rgb = oklab::oklab_to_rgb(lab[0], lab[1], lab[2]);
+            oklab::rgb_to_oklab(rgb[0], rgb[1], rgb[2])
+        };
+        assert!(aprox_eq_within(&lab, &lab2, 1e-3), "Oklab of {rgb:?}");
+
+        let [l, c, h] = oklch::rgb_to_oklch(rgb[0], rgb[1], rgb[2]);
+        let [l2, c2, h2] = {
+            let rgb = oklch::oklch_to_rgb(l, c, h);
+            oklch::rgb_to_oklch(rgb[0], rgb[1], rgb[2])
+        };
+        assert!(
+            aprox_eq_within(&[l2, c2], &[l, c], 1e-3),
+            "Oklch of {rgb:?}"
+        );
+        if c > 1e-3 {
+            assert!(hue_distance(h, h2) < 0.1, "Oklch hue of {rgb:?}");
+        }
+
+        let cmyk = cmyk::rgb_to_cmyk(rgb[0], rgb[1], rgb[2]);
+        let cmyk2 = {
+            let rgb = cmyk::cmyk_to_rgb(cmyk[0], cmyk[1], cmyk[2], cmyk[3]);
+            cmyk::rgb_to_cmyk(rgb[0], rgb[1], rgb[2])
+        };
+        assert!(aprox_eq_within(&cmyk, &cmyk2, 1e-3), "CMYK of {rgb:?}");
+    }
+}
+
+#[test]
+#[allow(clippy::many_single_char_names)]
+fn ranges() {
+    for rgb in colors() {
+        let [h, s, v] = hsv::rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
+        assert!((0.0..360.0).contains(&h), "HSV hue {h} of {rgb:?}");
+        assert!((0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&v));
+
+        let [l, c, h] = oklch::rgb_to_oklch(rgb[0], rgb[1], rgb[2]);
+        assert!((0.0..360.0).contains(&h), "Oklch hue {h} of {rgb:?}");
+        assert!((-1e-4..=1.0 + 1e-4).contains(&l) && (0.0..=0.33).contains(&c));
+
+        let cmyk = cmyk::rgb_to_cmyk(rgb[0], rgb[1], rgb[2]);
+        assert!(
+            cmyk.iter().all(|x| (-1e-6..=1.0 + 1e-6).contains(x)),
+            "CMYK {cmyk:?} of {rgb:?}"
+        );
+    }
+}
+
+#[test]
+fn hue_360() {
+    let mut sampler = Sampler::new(360);
+    for _ in 0..100 {
+        let [a, b] = [sampler.next(), sampler.next()];
+        assert!(aprox_eq(
+            &hsv::hsv_to_rgb(360.0, a, b),
+            &hsv::hsv_to_rgb(0.0, a, b)
+        ));
+
+        let (l, c) = (a, b * 0.1);
+        assert!(aprox_eq(
+            &oklch::oklch_to_rgb(l, c, 360.0),
+            &oklch::oklch_to_rgb(l, c, 0.0)
+        ));
+    }
+
+    // A hue just below 360 must not round up to it
+    let [h, _, _] = hsv::rgb_to_hsv(1.0, 0.0, 1e-7);
+    assert!(h < 360.0);
+}
+
+#[test]
+fn zero_chroma() {
+    for gray in [0.0, 0.18, 0.5, 1.0] {
+        let rgb = [gray; 3];
+
+        let [_, c, _] = oklch::rgb_to_oklch(gray, gray, gray);
+        assert!(c < 1e-4, "chroma {c} of {gray}");
+        let [_, s, _] = hsv::rgb_to_hsv(gray, gray, gray);
+        assert!(s < 1e-4, "saturation {s} of {gray}");
+
+        // Any hue gives the same gray
+        let l = oklab::rgb_to_oklab(gray, gray, gray)[0];
+        for hue in [0.0, 90.0, 180.0, 270.0, 360.0] {
+            assert!(aprox_eq(&oklch::oklch_to_rgb(l, 0.0, hue), &rgb));
+            assert!(aprox_eq(&hsv::hsv_to_rgb(hue, 0.0, gray), &rgb));
+        }
+    }
+}
+
+#[test]
+fn negative_oklab() {
+    // Green is negative on a, blue on b and cyan on both
+    for (rgb, a, b) in [
+        ([0.0, 1.0, 0.0], false, true),
+        ([0.0, 0.0, 1.0], false, false),
+        ([0.0, 1.0, 1.0], false, false),
+    ] {
+        let lab = oklab::rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
+        assert_eq!((lab[1] > 0.0, lab[2] > 0.0), (a, b), "{rgb:?} is {lab:?}");
+        assert!(aprox_eq(&oklab::oklab_to_rgb(lab[0], lab[1], lab[2]), &rgb));
+    }
+
+    let lab = [0.5, -0.1, -0.1];
+    let rgb = oklab::oklab_to_rgb(lab[0], lab[1], lab[2]);
+    assert!(aprox_eq(&oklab::rgb_to_oklab(rgb[0], rgb[1], rgb[2]), &lab));
+}
+
+#[test]
+fn full_black() {
+    let mut sampler = Sampler::new(1);
+    for _ in 0..100 {
+        let [c, m, y] = sampler.rgb();
+        assert!(aprox_eq(&cmyk::cmyk_to_rgb(c, m, y, 1.0), &[0.0; 3]));
+    }
+
+    // Only K is needed for black and grays
+    assert!(aprox_eq(
+        &cmyk::rgb_to_cmyk(0.0, 0.0, 0.0),
+        &[0.0, 0.0, 0.0, 1.0]
+    ));
+    assert!(aprox_eq(
+        &cmyk::rgb_to_cmyk(0.25, 0.25, 0.25),
+        &[0.0, 0.0, 0.0, 0.75]
+    ));
+    assert!(aprox_eq(&cmyk::cmyk_to_rgb(0.0, 0.0, 0.0, 0.5), &[0.5; 3]));
+    assert!(aprox_eq(
+        &cmyk::cmyk_to_rgb(0.5, 0.0, 1.0, 0.5),
+        &[0.25, 0.5, 0.0]
+    ));
+}
+
+/// Rows of the HSV table in Wikipedia's "HSL and HSV" article,
+/// in the article's precision of 3 decimals and 0.1 degrees
+#[test]
+fn reference_hsv() {
+    const TABLE: [([f32; 3], [f32; 3]); 15] = [
+        ([0.750, 0.750, 0.000], [60.0, 1.000, 0.750]),
+        ([0.000, 0.500, 0.000], [120.0, 1.000, 0.500]),
+        ([0.500, 1.000, 1.000], [180.0, 0.500, 1.000]),
+        ([0.500, 0.500, 1.000], [240.0, 0.500, 1.000]),
+        ([0.750, 0.250, 0.750], [300.0, 0.667, 0.750]),
+        ([0.628, 0.643, 0.142], [61.8, 0.779, 0.643]),
+        ([0.255, 0.104, 0.918], [251.1, 0.887, 0.918]),
+        ([0.116, 0.675, 0.255], [134.9, 0.828, 0.675]),
+        ([0.941, 0.785, 0.053], [49.5, 0.944, 0.941]),
+        ([0.704, 0.187, 0.897], [283.7, 0.792, 0.897]),
+        ([0.931, 0.463, 0.316], [14.3, 0.661, 0.931]),
+        ([0.998, 0.974, 0.532], [56.9, 0.467, 0.998]),
+        ([0.099, 0.795, 0.591], [162.4, 0.875, 0.795]),
+        ([0.211, 0.149, 0.597], [248.3, 0.750, 0.597]),
+        ([0.495, 0.493, 0.721], [240.5, 0.316, 0.721]),
+    ];
+
+    for (rgb, expected) in TABLE {
+        let [h, s, v] = hsv::rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
+        assert!(hue_distance(h, expected[0]) < 0.2, "hue {h} of {rgb:?}");
+        assert!(
+            aprox_eq_within(&[s, v], &expected[1..], 2e-3),
+            "{rgb:?} is {:?}",
+            [h, s, v]
+        );
+    }
+}
+
+/// XYZ and Oklab pairs from Björn Ottosson's "A perceptual color space for
+/// image processing", converted to linear sRGB with the sRGB matrix
+#[test]
+fn reference_oklab() {
+    const TABLE: [([f32; 3], [f32; 3]); 4] = [
+        ([0.950, 1.000, 1.089], [1.000, 0.000, 0.000]),
+        ([1.000, 0.000, 0.000], [0.450, 1.236, -0.019]),
+        ([0.000, 1.000, 0.000], [0.922, -0.671, 0.263]),
+        ([0.000, 0.000, 1.000], [0.153, -1.415, -0.449]),
+    ];
+
+    #[allow(clippy::excessive_precision, clippy::unreadable_literal)]
+    fn xyz_to_rgb([x, y, z]: [f32; 3]) -> [f32; 3] {
+        [
+            3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+            -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+            0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+        ]
+    }
+
+    for (xyz, expected) in TABLE {
+        let rgb = xyz_to_rgb(xyz);
+        let lab = oklab::rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
+        assert!(aprox_eq_within(&lab, &expected, 2e-3), "{xyz:?} is {lab:?}");
+    }
+}
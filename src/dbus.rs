@@ -0,0 +1,273 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::{Arc, Mutex};
+
+use cosmic::iced::futures::{
+    channel::mpsc::{self, UnboundedSender},
+    stream, SinkExt, StreamExt,
+};
+use cosmic::iced::Subscription;
+use zbus::{fdo, interface, SignalContext};
+
+use crate::colorspace::parse_srgb;
+use crate::portal;
+
+/// Bus name of the service, try it with `busctl --user call io.github.pixeldoted.ColorPicker
+/// /io/github/pixeldoted/ColorPicker io.github.pixeldoted.ColorPicker GetCurrentColors`
+pub const NAME: &str = "io.github.pixeldoted.ColorPicker";
+pub const PATH: &str = "/io/github/pixeldoted/ColorPicker";
+
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// The service is running, colors sent to the publisher are
+    /// returned by `GetCurrentColors` and announced by `ColorChanged`
+    Ready(Publisher),
+    /// Colors to add as new columns
+    Open(Vec<[f32; 3]>),
+}
+
+/// Sends the hex colors of the app's columns to the service
+#[derive(Clone, Debug)]
+pub struct Publisher(UnboundedSender<Vec<String>>);
+
+impl Publisher {
+    pub fn publish(&self, colors: Vec<String>) {
+        if let Err(e) = self.0.unbounded_send(colors) {
+            log::error!("Failed to publish colors: {e}");
+        }
+    }
+}
+
+/// Lets other apps pick colors and read the app's columns
+struct Service {
+    colors: Arc<Mutex<Vec<String>>>,
+    events: mpsc::Sender<Event>,
+}
+
+#[interface(name = "io.github.pixeldoted.ColorPicker")]
+impl Service {
+    /// Asks the user to pick a color from the screen, adds it as a column
+    /// and returns it as hex
+    async fn pick_color(&self) -> fdo::Result<String> {
+        let rgb = portal::pick_color()
+            .await
+            .ok_or_else(|| fdo::Error::Failed("No color was picked".into()))?;
+
+        self.send(Event::Open(vec![rgb])).await;
+        Ok(to_hex(rgb))
+    }
+
+    /// Adds each color as a column, colors are hex or 8-bit sRGB
+    async fn open_with_color(&self, colors: Vec<String>) -> fdo::Result<()> {
+        let colors = colors
+            .iter()
+            .map(|color| {
+                parse_color(color)
+                    .ok_or_else(|| fdo::Error::InvalidArgs(format!("Invalid color: {color}")))
+            })
+            .collect::<fdo::Result<Vec<_>>>()?;
+
+        self.send(Event::Open(colors)).await;
+        Ok(())
+    }
+
+    /// Hex colors of the columns, left to right
+    fn get_current_colors(&self) -> Vec<String> {
+        self.colors.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    #[zbus(signal)]
+    async fn color_changed(
+        context: &SignalContext<'_>,
+        index: u32,
+        color: &str,
+    ) -> zbus::Result<()>;
+}
+
+impl Service {
+    async fn send(&self, event: Event) {
+        if let Err(e) = self.events.clone().send(event).await {
+            log::error!("Failed to send D-Bus request to the app: {e}");
+        }
+    }
+}
+
+/// Runs the service on the session bus
+pub fn subscription() -> Subscription<Event> {
+    Subscription::run(|| {
+        cosmic::iced::stream::channel(8, |mut output: mpsc::Sender<Event>| async move {
+            let (publisher, published) = mpsc::unbounded();
+            let (events, requests) = mpsc::channel(8);
+            let colors = Arc::new(Mutex::new(Vec::new()));
+            let service = Service {
+                colors: colors.clone(),
+                events,
+            };
+
+            let connection = match serve(service).await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    log::error!("Failed to start the D-Bus service: {e}");
+                    return;
+                }
+            };
+            let interface = match connection
+                .object_server()
+                .interface::<_, Service>(PATH)
+                .await
+            {
+                Ok(interface) => interface,
+                Err(e) => {
+                    log::error!("Failed to get the D-Bus interface: {e}");
+                    return;
+                }
+            };
+
+            if output
+                .send(Event::Ready(Publisher(publisher)))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            enum Input {
+                Request(Event),
+                Colors(Vec<String>),
+            }
+            let mut inputs =
+                stream::select(requests.map(Input::Request), published.map(Input::Colors));
+            while let Some(input) = inputs.next().await {
+                match input {
+                    Input::Request(event) => {
+                        if output.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    Input::Colors(new) => {
+                        let old = match colors.lock() {
+                            Ok(mut colors) => std::mem::replace(&mut *colors, new.clone()),
+                            Err(_) => return,
+                        };
+                        for (index, color) in new.iter().enumerate() {
+                            if old.get(index) == Some(color) {
+                                continue;
+                            }
+
+                            #[allow(clippy::cast_possible_truncation)]
+                            let signal = Service::color_changed(
+                                interface.signal_context(),
+                                index as u32,
+                                color,
+                            );
+                            if let Err(e) = signal.await {
+                                log::error!("Failed to emit ColorChanged: {e}");
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    })
+}
+
+async fn serve(service: Service) -> zbus::Result<zbus::Connection> {
+    zbus::connection::Builder::session()?
+        .name(NAME)?
+        .serve_at(PATH, service)?
+        .build()
+        .await
+}
+
+/// Parses `#rrggbb`, `rrggbb` or 8-bit sRGB like `255, 128, 0`
+pub fn parse_color(color: &str) -> Option<[f32; 3]> {
+    let color = color.trim();
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if let Ok(&[r, g, b]) = hex::decode(hex).as_deref() {
+        return Some([r, g, b].map(|c| f32::from(c) / 255.0));
+    }
+
+    parse_srgb(color)
+}
+
+pub fn to_hex(rgb: [f32; 3]) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let srgb = rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{}", hex::encode(srgb))
+}
+
+#[cfg(test)]
+mod test {
+    use std::os::unix::net::UnixStream;
+    use std::sync::{Arc, Mutex};
+
+    use cosmic::iced::futures::{channel::mpsc, future, StreamExt};
+
+    use super::{parse_color, to_hex, Event, Service, NAME, PATH};
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#ff8000"), Some([1.0, 128.0 / 255.0, 0.0]));
+        assert_eq!(parse_color("FF8000"), Some([1.0, 128.0 / 255.0, 0.0]));
+        assert_eq!(parse_color("255, 128, 0"), Some([1.0, 128.0 / 255.0, 0.0]));
+        assert_eq!(parse_color("#ff80"), None);
+        assert_eq!(parse_color("orange"), None);
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(to_hex([1.0, 128.0 / 255.0, 0.0]), "#ff8000");
+        assert_eq!(to_hex([1.5, -0.5, 0.0]), "#ff0000");
+    }
+
+    #[test]
+    fn service() {
+        zbus::block_on(async {
+            let (events, mut requests) = mpsc::channel(8);
+            let service = Service {
+                colors: Arc::new(Mutex::new(vec!["#ff8000".into()])),
+                events,
+            };
+
+            // A private connection, so the test needs no session bus
+            let (server, client) = UnixStream::pair().unwrap();
+            let (_server, client) = future::try_join(
+                zbus::connection::Builder::unix_stream(server)
+                    .server(zbus::Guid::generate())
+                    .unwrap()
+                    .p2p()
+                    .serve_at(PATH, service)
+                    .unwrap()
+                    .build(),
+                zbus::connection::Builder::unix_stream(client).p2p().build(),
+            )
+            .await
+            .unwrap();
+
+            let colors = vec!["#0000ff", "255, 128, 0"];
+            client
+                .call_method(None::<&str>, PATH, Some(NAME), "OpenWithColor", &(colors,))
+                .await
+                .unwrap();
+            match requests.next().await {
+                Some(Event::Open(colors)) => {
+                    assert_eq!(colors, [[0.0, 0.0, 1.0], [1.0, 128.0 / 255.0, 0.0]]);
+                }
+                _ => panic!("OpenWithColor sent no colors"),
+            }
+
+            let invalid = vec!["orange"];
+            assert!(client
+                .call_method(None::<&str>, PATH, Some(NAME), "OpenWithColor", &(invalid,))
+                .await
+                .is_err());
+
+            let reply = client
+                .call_method(None::<&str>, PATH, Some(NAME), "GetCurrentColors", &())
+                .await
+                .unwrap();
+            let colors: Vec<String> = reply.body().deserialize().unwrap();
+            assert_eq!(colors, ["#ff8000"]);
+        });
+    }
+}
@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+
+use cosmic::cosmic_config::CosmicConfigEntry;
+use cosmic::cosmic_theme::palette::{Srgb, Srgba};
+use cosmic::cosmic_theme::{Theme, ThemeBuilder, ThemeMode};
+
+use crate::fl;
+
+/// Colors of a COSMIC theme that columns can be read from or written to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Accent,
+    Background,
+    PrimaryContainer,
+    SecondaryContainer,
+    Success,
+    Warning,
+    Destructive,
+}
+
+impl Role {
+    pub const ALL: [Role; 7] = [
+        Role::Accent,
+        Role::Background,
+        Role::PrimaryContainer,
+        Role::SecondaryContainer,
+        Role::Success,
+        Role::Warning,
+        Role::Destructive,
+    ];
+
+    fn get(self, theme: &Theme) -> [f32; 3] {
+        let color = match self {
+            Role::Accent => theme.accent.base,
+            Role::Background => theme.background.base,
+            Role::PrimaryContainer => theme.primary.base,
+            Role::SecondaryContainer => theme.secondary.base,
+            Role::Success => theme.success.base,
+            Role::Warning => theme.warning.base,
+            Role::Destructive => theme.destructive.base,
+        };
+
+        [color.red, color.green, color.blue]
+    }
+
+    fn set(self, builder: &mut ThemeBuilder, [r, g, b]: [f32; 3]) {
+        match self {
+            Role::Accent => builder.accent = Some(Srgb::new(r, g, b)),
+            Role::Background => builder.bg_color = Some(Srgba::new(r, g, b, 1.0)),
+            Role::PrimaryContainer => {
+                builder.primary_container_bg = Some(Srgba::new(r, g, b, 1.0));
+            }
+            Role::SecondaryContainer => {
+                builder.secondary_container_bg = Some(Srgba::new(r, g, b, 1.0));
+            }
+            Role::Success => builder.success = Some(Srgb::new(r, g, b)),
+            Role::Warning => builder.warning = Some(Srgb::new(r, g, b)),
+            Role::Destructive => builder.destructive = Some(Srgb::new(r, g, b)),
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Accent => f.write_str(&fl!("role-accent")),
+            Role::Background => f.write_str(&fl!("role-background")),
+            Role::PrimaryContainer => f.write_str(&fl!("role-primary-container")),
+            Role::SecondaryContainer => f.write_str(&fl!("role-secondary-container")),
+            Role::Success => f.write_str(&fl!("role-success")),
+            Role::Warning => f.write_str(&fl!("role-warning")),
+            Role::Destructive => f.write_str(&fl!("role-destructive")),
+        }
+    }
+}
+
+/// Whether the desktop uses its dark theme
+pub fn is_dark() -> bool {
+    let config = match ThemeMode::config() {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("Failed to open the theme mode config: {e}");
+            return true;
+        }
+    };
+
+    match ThemeMode::get_entry(&config) {
+        Ok(mode) | Err((_, mode)) => mode.is_dark,
+    }
+}
+
+/// Colors of the desktop's current theme, read from cosmic-config
+pub fn read() -> Vec<(Role, [f32; 3])> {
+    let config = if is_dark() {
+        Theme::dark_config()
+    } else {
+        Theme::light_config()
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("Failed to open the theme config: {e}");
+            return Vec::new();
+        }
+    };
+    // Missing keys keep COSMIC's defaults
+    let theme = match Theme::get_entry(&config) {
+        Ok(theme) | Err((_, theme)) => theme,
+    };
+
+    Role::ALL
+        .into_iter()
+        .map(|role| (role, role.get(&theme)))
+        .collect()
+}
+
+/// A theme with `colors` over COSMIC's default dark or light theme
+pub fn builder(dark: bool, colors: &[(Role, [f32; 3])]) -> ThemeBuilder {
+    let mut builder = if dark {
+        ThemeBuilder::dark()
+    } else {
+        ThemeBuilder::light()
+    };
+    for (role, rgb) in colors {
+        role.set(&mut builder, *rgb);
+    }
+
+    builder
+}
+
+/// Sets `colors` in the desktop's dark or light theme, keeping its other settings
+pub fn apply(dark: bool, colors: &[(Role, [f32; 3])]) {
+    let (builder_config, theme_config) = if dark {
+        (ThemeBuilder::dark_config(), Theme::dark_config())
+    } else {
+        (ThemeBuilder::light_config(), Theme::light_config())
+    };
+    let (builder_config, theme_config) = match (builder_config, theme_config) {
+        (Ok(builder_config), Ok(theme_config)) => (builder_config, theme_config),
+        (Err(e), _) | (_, Err(e)) => {
+            log::error!("Failed to open the theme config: {e}");
+            return;
+        }
+    };
+
+    // Missing keys keep COSMIC's defaults, other errors would overwrite the user's theme
+    let mut builder = match ThemeBuilder::get_entry(&builder_config) {
+        Ok(builder) => builder,
+        Err((errors, builder)) => {
+            if let Some(e) = errors.iter().find(|e| e.is_err()) {
+                log::error!("Failed to read the theme builder: {e}");
+                return;
+            }
+            builder
+        }
+    };
+    for (role, rgb) in colors {
+        role.set(&mut builder, *rgb);
+    }
+    if let Err(e) = builder.write_entry(&builder_config) {
+        log::error!("Failed to write the theme builder: {e}");
+        return;
+    }
+
+    // The desktop reads the built theme, not the builder
+    if let Err(e) = builder.build().write_entry(&theme_config) {
+        log::error!("Failed to write the theme: {e}");
+    }
+}
+
+/// Sets the custom accent color of the desktop's current theme
+pub fn set_accent(rgb: [f32; 3]) {
+    apply(is_dark(), &[(Role::Accent, rgb)]);
+}
+
+/// Serializes `builder` like the theme export of COSMIC Settings
+pub fn to_ron(builder: &ThemeBuilder) -> Option<String> {
+    match ron::ser::to_string_pretty(builder, ron::ser::PrettyConfig::default()) {
+        Ok(ron) => Some(ron),
+        Err(e) => {
+            log::error!("Failed to serialize the theme: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{builder, Role};
+    use cosmic::cosmic_theme::palette::{Srgb, Srgba};
+
+    #[test]
+    fn builder_roles() {
+        let builder = builder(
+            false,
+            &[
+                (Role::Accent, [0.1, 0.2, 0.3]),
+                (Role::Background, [0.9, 0.9, 0.8]),
+                (Role::Destructive, [0.8, 0.1, 0.1]),
+            ],
+        );
+
+        assert_eq!(builder.accent, Some(Srgb::new(0.1, 0.2, 0.3)));
+        assert_eq!(builder.bg_color, Some(Srgba::new(0.9, 0.9, 0.8, 1.0)));
+        assert_eq!(builder.destructive, Some(Srgb::new(0.8, 0.1, 0.1)));
+        // Roles without a column keep COSMIC's defaults
+        assert_eq!(builder.success, None);
+        assert!(!builder.palette.is_dark());
+    }
+}
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+
+use lcms2::{ColorSpaceSignature, InfoType, Intent, Locale, PixelFormat, Profile, Transform};
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::colorspace::srgb_to_linear;
+
+const IDENTITY: [[f32; 4]; 3] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+];
+
+const COLORD: &str = "org.freedesktop.ColorManager";
+
+/// Converts sRGB previews to the color space of the monitor, e.g. a wide-gamut one
+pub struct DisplayProfile {
+    /// Description stored in the profile
+    pub name: String,
+    transform: Transform<[f32; 3], [f32; 3]>,
+    /// Columns of a matrix from linear sRGB to the linear display, for the graph shaders
+    matrix: [[f32; 4]; 3],
+}
+
+impl DisplayProfile {
+    pub fn load(path: &Path) -> Option<Self> {
+        let profile = match Profile::new_file(path) {
+            Ok(profile) => profile,
+            Err(e) => {
+                log::error!("Failed to load ICC profile {path:?}: {e}");
+                return None;
+            }
+        };
+        if profile.color_space() != ColorSpaceSignature::RgbData {
+            log::error!("{path:?} is not an RGB profile");
+            return None;
+        }
+
+        let transform = match Transform::new(
+            &Profile::new_srgb(),
+            PixelFormat::RGB_FLT,
+            &profile,
+            PixelFormat::RGB_FLT,
+            Intent::RelativeColorimetric,
+        ) {
+            Ok(transform) => transform,
+            Err(e) => {
+                log::error!("Failed to create a transform for {path:?}: {e}");
+                return None;
+            }
+        };
+
+        // Graphs are drawn on the GPU, so the profile is approximated
+        // by where the sRGB primaries land with an sRGB tone curve
+        let matrix = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].map(|primary| {
+            let mut display = [[0.0; 3]];
+            transform.transform_pixels(&[primary], &mut display);
+            let [r, g, b] = display[0].map(srgb_to_linear);
+            [r, g, b, 0.0]
+        });
+
+        let name = profile
+            .info(InfoType::Description, Locale::none())
+            .or_else(|| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+
+        Some(Self {
+            name,
+            transform,
+            matrix,
+        })
+    }
+}
+
+/// The color to send to the compositor to show `rgb` on a monitor with `profile`,
+/// `None` shows it unmanaged
+pub fn to_display(profile: Option<&DisplayProfile>, rgb: [f32; 3]) -> [f32; 3] {
+    let Some(profile) = profile else {
+        return rgb;
+    };
+
+    let mut display = [[0.0; 3]];
+    profile.transform.transform_pixels(&[rgb], &mut display);
+    display[0]
+}
+
+/// Matrix for `to_display` in the graph shaders, identity when unmanaged
+pub fn matrix(profile: Option<&DisplayProfile>) -> [[f32; 4]; 3] {
+    profile.map_or(IDENTITY, |profile| profile.matrix)
+}
+
+/// Asks colord for the profile of the first display that has one
+pub async fn colord_profile() -> Option<PathBuf> {
+    match find_colord_profile().await {
+        Ok(path) => path,
+        Err(e) => {
+            log::error!("Failed to get the display profile from colord: {e}");
+            None
+        }
+    }
+}
+
+async fn find_colord_profile() -> zbus::Result<Option<PathBuf>> {
+    let connection = zbus::Connection::system().await?;
+    let manager =
+        zbus::Proxy::new(&connection, COLORD, "/org/freedesktop/ColorManager", COLORD).await?;
+    let devices: Vec<OwnedObjectPath> = manager.call("GetDevicesByKind", &("display",)).await?;
+
+    for device in devices {
+        let device = zbus::Proxy::new(
+            &connection,
+            COLORD,
+            device,
+            "org.freedesktop.ColorManager.Device",
+        )
+        .await?;
+        // The first profile is the default one
+        let profiles: Vec<OwnedObjectPath> = device.get_property("Profiles").await?;
+        let Some(profile) = profiles.into_iter().next() else {
+            continue;
+        };
+
+        let profile = zbus::Proxy::new(
+            &connection,
+            COLORD,
+            profile,
+            "org.freedesktop.ColorManager.Profile",
+        )
+        .await?;
+        let filename: String = profile.get_property("Filename").await?;
+        if !filename.is_empty() {
+            return Ok(Some(PathBuf::from(filename)));
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::time::{Duration, Instant};
+
+/// Maximum number of undo steps kept in memory
+const LIMIT: usize = 100;
+
+/// Pause after which a record starts a new step even in the same group,
+/// so separate drags or key presses on a channel are undone one by one
+const GAP: Duration = Duration::from_millis(500);
+
+/// An undo/redo stack of snapshots.
+///
+/// Consecutive records sharing the same group are coalesced into a single step,
+/// so dragging a slider or typing into a field can be undone in one go.
+pub struct History<T, G> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    group: Option<G>,
+    /// Time of the last record of `group`
+    last: Option<Instant>,
+}
+
+impl<T, G> Default for History<T, G> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            group: None,
+            last: None,
+        }
+    }
+}
+
+impl<T: Clone, G: PartialEq> History<T, G> {
+    /// Records `state` as it was before an edit.
+    pub fn record(&mut self, state: &T, group: Option<G>) {
+        self.record_at(state, group, Instant::now());
+    }
+
+    fn record_at(&mut self, state: &T, group: Option<G>, now: Instant) {
+        let recent = self.last.is_some_and(|last| now.duration_since(last) < GAP);
+        self.last = Some(now);
+        if group.is_some() && self.group == group && recent {
+            return;
+        }
+
+        self.undo.push(state.clone());
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+
+        self.redo.clear();
+        self.group = group;
+    }
+
+    /// Ends the current group, the next record will always create a new step.
+    pub fn seal(&mut self) {
+        self.group = None;
+    }
+
+    pub fn undo(&mut self, current: &mut T) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+
+        self.redo.push(std::mem::replace(current, previous));
+        self.group = None;
+        true
+    }
+
+    pub fn redo(&mut self, current: &mut T) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+
+        self.undo.push(std::mem::replace(current, next));
+        self.group = None;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use super::{History, GAP};
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::<u32, ()>::default();
+        let mut state = 0;
+
+        history.record(&state, None);
+        state = 1;
+        history.record(&state, None);
+        state = 2;
+
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 1);
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 0);
+        assert!(!history.undo(&mut state));
+
+        assert!(history.redo(&mut state));
+        assert_eq!(state, 1);
+        assert!(history.redo(&mut state));
+        assert_eq!(state, 2);
+        assert!(!history.redo(&mut state));
+    }
+
+    #[test]
+    fn coalesce() {
+        let mut history = History::<u32, usize>::default();
+        let mut state = 0;
+
+        for value in 1..10 {
+            history.record(&state, Some(0));
+            state = value;
+        }
+
+        history.record(&state, Some(1));
+        state = 20;
+
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 9);
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 0);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn seal() {
+        let mut history = History::<u32, usize>::default();
+        let mut state = 0;
+
+        history.record(&state, Some(0));
+        state = 1;
+        history.seal();
+        history.record(&state, Some(0));
+        state = 2;
+
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 1);
+    }
+
+    #[test]
+    fn gap() {
+        let mut history = History::<u32, usize>::default();
+        let mut state = 0;
+        let start = Instant::now();
+
+        history.record_at(&state, Some(0), start);
+        state = 1;
+        history.record_at(&state, Some(0), start + GAP / 2);
+        state = 2;
+        history.record_at(&state, Some(0), start + GAP * 2);
+        state = 3;
+
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 2);
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 0);
+    }
+
+    #[test]
+    fn record_clears_redo() {
+        let mut history = History::<u32, ()>::default();
+        let mut state = 0;
+
+        history.record(&state, None);
+        state = 1;
+        assert!(history.undo(&mut state));
+        assert!(history.can_redo());
+
+        history.record(&state, None);
+        assert!(!history.can_redo());
+    }
+}
@@ -0,0 +1,424 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+
+use cosmic::cosmic_config::{self, ConfigGet, ConfigSet};
+use cosmic::iced::keyboard::{key::Named, Key, Modifiers};
+use cosmic::widget::menu::{key_bind::Modifier, KeyBind};
+
+use crate::{
+    app::{Action, CopyFormat},
+    colorspace::ColorSpaceCombo,
+    fl,
+    palette::Source,
+    sampling::SampleMode,
+};
+
+/// Config key of the user's overrides, action id to shortcut.
+/// An empty shortcut unbinds the action
+const CONFIG_KEY: &str = "keybinds";
+
+/// Every bindable action with its config id and default shortcut
+pub const BINDABLE: [(&str, Action, &str); 51] = [
+    ("undo", Action::Undo, "Ctrl+z"),
+    ("redo", Action::Redo, "Ctrl+Shift+z"),
+    ("copy", Action::Copy(CopyFormat::Current), "Ctrl+c"),
+    ("copy-hex", Action::Copy(CopyFormat::Hex), "Ctrl+Shift+c"),
+    (
+        "copy-rgb",
+        Action::Copy(CopyFormat::Space(ColorSpaceCombo::Rgb)),
+        "",
+    ),
+    (
+        "copy-hsv",
+        Action::Copy(CopyFormat::Space(ColorSpaceCombo::Hsv)),
+        "",
+    ),
+    (
+        "copy-oklab",
+        Action::Copy(CopyFormat::Space(ColorSpaceCombo::Oklab)),
+        "",
+    ),
+    (
+        "copy-oklch",
+        Action::Copy(CopyFormat::Space(ColorSpaceCombo::Oklch)),
+        "",
+    ),
+    (
+        "copy-cmyk",
+        Action::Copy(CopyFormat::Space(ColorSpaceCombo::Cmyk)),
+        "",
+    ),
+    (
+        "copy-hdr",
+        Action::Copy(CopyFormat::Space(ColorSpaceCombo::Hdr)),
+        "",
+    ),
+    ("pick-screen", Action::PickScreen, "Ctrl+p"),
+    ("sample-pixel", Action::SampleMode(SampleMode::Pixel), ""),
+    (
+        "sample-average",
+        Action::SampleMode(SampleMode::Average),
+        "",
+    ),
+    ("sample-median", Action::SampleMode(SampleMode::Median), ""),
+    (
+        "sample-dominant",
+        Action::SampleMode(SampleMode::Dominant),
+        "",
+    ),
+    ("sample-size-3", Action::SampleSize(3), ""),
+    ("sample-size-5", Action::SampleSize(5), ""),
+    ("sample-size-11", Action::SampleSize(11), ""),
+    ("sample-size-21", Action::SampleSize(21), ""),
+    ("add-column", Action::AddColumn, "Ctrl+n"),
+    ("remove-column", Action::RemoveColumn, "Ctrl+w"),
+    ("duplicate-column", Action::DuplicateColumn, "Ctrl+d"),
+    (
+        "space-rgb",
+        Action::SwitchSpace(ColorSpaceCombo::Rgb),
+        "Ctrl+1",
+    ),
+    (
+        "space-hsv",
+        Action::SwitchSpace(ColorSpaceCombo::Hsv),
+        "Ctrl+2",
+    ),
+    (
+        "space-oklab",
+        Action::SwitchSpace(ColorSpaceCombo::Oklab),
+        "Ctrl+3",
+    ),
+    (
+        "space-oklch",
+        Action::SwitchSpace(ColorSpaceCombo::Oklch),
+        "Ctrl+4",
+    ),
+    (
+        "space-cmyk",
+        Action::SwitchSpace(ColorSpaceCombo::Cmyk),
+        "Ctrl+5",
+    ),
+    (
+        "space-hdr",
+        Action::SwitchSpace(ColorSpaceCombo::Hdr),
+        "Ctrl+6",
+    ),
+    ("toggle-graphs", Action::ToggleGraphs, "Ctrl+g"),
+    ("toggle-expanded", Action::ToggleExpanded, "Ctrl+e"),
+    ("toggle-compact", Action::ToggleCompact, "Ctrl+m"),
+    ("always-on-top", Action::AlwaysOnTop, ""),
+    ("color-managed", Action::ColorManaged, ""),
+    ("soft-proof", Action::SoftProof, "Ctrl+Shift+p"),
+    ("hdr-mode", Action::HdrMode, ""),
+    ("named-colors", Action::NamedColors, "Ctrl+f"),
+    ("export-sheet", Action::ExportSheet, ""),
+    ("desktop-theme", Action::DesktopTheme, ""),
+    ("read-desktop-theme", Action::ReadDesktopTheme, ""),
+    ("set-accent-color", Action::SetAccentColor, ""),
+    (
+        "extract-palette-file",
+        Action::ExtractPalette(Source::File),
+        "",
+    ),
+    (
+        "extract-palette-screenshot",
+        Action::ExtractPalette(Source::Screenshot),
+        "",
+    ),
+    ("palette-size-4", Action::PaletteSize(4), ""),
+    ("palette-size-6", Action::PaletteSize(6), ""),
+    ("palette-size-8", Action::PaletteSize(8), ""),
+    ("palette-size-12", Action::PaletteSize(12), ""),
+    ("import-palette", Action::ImportPalette, ""),
+    ("pick-history", Action::PickHistory, "Ctrl+h"),
+    ("settings", Action::Settings, "Ctrl+,"),
+    ("keybinds", Action::Keybinds, ""),
+    ("about", Action::About, ""),
+];
+
+/// Named keys that can be used in a shortcut
+const NAMED: [(Named, &str); 22] = [
+    (Named::Delete, "Delete"),
+    (Named::Insert, "Insert"),
+    (Named::Home, "Home"),
+    (Named::End, "End"),
+    (Named::PageUp, "PageUp"),
+    (Named::PageDown, "PageDown"),
+    (Named::ArrowUp, "Up"),
+    (Named::ArrowDown, "Down"),
+    (Named::ArrowLeft, "Left"),
+    (Named::ArrowRight, "Right"),
+    (Named::Enter, "Enter"),
+    (Named::Space, "Space"),
+    (Named::F1, "F1"),
+    (Named::F2, "F2"),
+    (Named::F3, "F3"),
+    (Named::F4, "F4"),
+    (Named::F5, "F5"),
+    (Named::F6, "F6"),
+    (Named::F7, "F7"),
+    (Named::F8, "F8"),
+    (Named::F9, "F9"),
+    (Named::F10, "F10"),
+];
+
+/// Loads the user's overrides
+pub fn load(config: &cosmic_config::Config) -> HashMap<String, String> {
+    match config.get(CONFIG_KEY) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            log::info!("No keybinds loaded: {e}");
+            HashMap::new()
+        }
+    }
+}
+
+pub fn save(config: &cosmic_config::Config, overrides: &HashMap<String, String>) {
+    if let Err(e) = config.set(CONFIG_KEY, overrides) {
+        log::error!("Failed to save keybinds: {e}");
+    }
+}
+
+/// The shortcut of `BINDABLE[index]`, taking overrides into account
+pub fn shortcut(overrides: &HashMap<String, String>, index: usize) -> &str {
+    let (id, _, default) = BINDABLE[index];
+    overrides.get(id).map_or(default, String::as_str)
+}
+
+/// Builds the keybinds used by the menus, ignoring invalid
+/// and conflicting shortcuts
+pub fn build(overrides: &HashMap<String, String>) -> HashMap<KeyBind, Action> {
+    let mut keybinds = HashMap::new();
+    for (index, (id, action, _)) in BINDABLE.iter().enumerate() {
+        let shortcut = shortcut(overrides, index);
+        if shortcut.is_empty() {
+            continue;
+        }
+
+        let Some(key_bind) = parse(shortcut) else {
+            log::warn!("Invalid shortcut {shortcut:?} for {id}");
+            continue;
+        };
+
+        if keybinds.contains_key(&key_bind) {
+            log::warn!("Shortcut {shortcut:?} for {id} is already in use");
+            continue;
+        }
+        keybinds.insert(key_bind, *action);
+    }
+
+    keybinds
+}
+
+/// Returns the index of another action using `shortcut`
+pub fn conflict(
+    overrides: &HashMap<String, String>,
+    index: usize,
+    shortcut: &str,
+) -> Option<usize> {
+    let key_bind = parse(shortcut)?;
+    (0..BINDABLE.len())
+        .find(|i| *i != index && parse(self::shortcut(overrides, *i)).as_ref() == Some(&key_bind))
+}
+
+/// Builds a shortcut from a key press, returns `None` for lone modifiers
+/// and keys that can't be bound
+pub fn from_key(key: &Key, modifiers: Modifiers) -> Option<String> {
+    let mut key_bind = KeyBind {
+        modifiers: Vec::with_capacity(4),
+        key: match key {
+            Key::Character(c) => Key::Character(c.to_lowercase().into()),
+            Key::Named(named) if NAMED.iter().any(|(n, _)| n == named) => key.clone(),
+            _ => return None,
+        },
+    };
+
+    if modifiers.logo() {
+        key_bind.modifiers.push(Modifier::Super);
+    }
+    if modifiers.control() {
+        key_bind.modifiers.push(Modifier::Ctrl);
+    }
+    if modifiers.alt() {
+        key_bind.modifiers.push(Modifier::Alt);
+    }
+    if modifiers.shift() {
+        key_bind.modifiers.push(Modifier::Shift);
+    }
+
+    Some(format(&key_bind))
+}
+
+/// Parses a shortcut like `Ctrl+Shift+z`
+pub fn parse(shortcut: &str) -> Option<KeyBind> {
+    // Splitting from the right keeps `Ctrl++` working
+    let (modifiers, key) = match shortcut.rsplit_once('+') {
+        Some((modifiers, "")) => (modifiers.strip_suffix('+')?, "+"),
+        Some((modifiers, key)) => (modifiers, key),
+        None => ("", shortcut),
+    };
+
+    let modifiers = modifiers
+        .split('+')
+        .filter(|m| !m.is_empty())
+        .map(|m| match m {
+            "Super" => Some(Modifier::Super),
+            "Ctrl" => Some(Modifier::Ctrl),
+            "Alt" => Some(Modifier::Alt),
+            "Shift" => Some(Modifier::Shift),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let key = match NAMED.iter().find(|(_, name)| *name == key) {
+        Some((named, _)) => Key::Named(*named),
+        None if key.chars().count() == 1 => Key::Character(key.to_lowercase().into()),
+        None => return None,
+    };
+
+    Some(KeyBind { modifiers, key })
+}
+
+pub fn format(key_bind: &KeyBind) -> String {
+    let mut shortcut = String::new();
+    for modifier in &key_bind.modifiers {
+        shortcut.push_str(match modifier {
+            Modifier::Super => "Super+",
+            Modifier::Ctrl => "Ctrl+",
+            Modifier::Alt => "Alt+",
+            Modifier::Shift => "Shift+",
+        });
+    }
+
+    match &key_bind.key {
+        Key::Character(c) => shortcut.push_str(c),
+        Key::Named(named) => {
+            if let Some((_, name)) = NAMED.iter().find(|(n, _)| n == named) {
+                shortcut.push_str(name);
+            }
+        }
+        _ => (),
+    }
+
+    shortcut
+}
+
+/// Localized name of `BINDABLE[index]`
+pub fn label(index: usize) -> String {
+    match BINDABLE[index].1 {
+        Action::Undo => fl!("undo"),
+        Action::Redo => fl!("redo"),
+        Action::Copy(CopyFormat::Current) => fl!("copy"),
+        Action::Copy(CopyFormat::Hex) => fl!("copy-as", format = "Hex"),
+        Action::Copy(CopyFormat::Space(space)) => fl!("copy-as", format = space.to_string()),
+        Action::PickScreen => fl!("pick-screen"),
+        Action::SampleMode(mode) => fl!(
+            "keybind-option",
+            group = fl!("sampling"),
+            option = mode.to_string()
+        ),
+        Action::SampleSize(size) => fl!(
+            "keybind-option",
+            group = fl!("sampling"),
+            option = fl!("sample-size", size = size)
+        ),
+        Action::AddColumn => fl!("add-column"),
+        Action::RemoveColumn => fl!("remove-column"),
+        Action::DuplicateColumn => fl!("duplicate"),
+        Action::SwitchSpace(space) => fl!("switch-space", space = space.to_string()),
+        Action::ToggleGraphs => fl!("graphs"),
+        Action::ToggleExpanded => fl!("expanded"),
+        Action::ToggleCompact => fl!("compact"),
+        Action::AlwaysOnTop => fl!("always-on-top"),
+        Action::ColorManaged => fl!("color-managed"),
+        Action::SoftProof => fl!("soft-proof"),
+        Action::HdrMode => fl!("hdr-mode"),
+        Action::ProofTarget(target) => fl!(
+            "keybind-option",
+            group = fl!("proof-target"),
+            option = target.to_string()
+        ),
+        Action::SnapPalette(palette) => fl!(
+            "keybind-option",
+            group = fl!("snap-palette"),
+            option = palette.map_or_else(|| fl!("snap-off"), |palette| palette.to_string())
+        ),
+        Action::ImportPalette => fl!("import-palette"),
+        Action::NamedColors => fl!("named-colors"),
+        Action::ExportSheet => fl!("export-sheet-title"),
+        Action::DesktopTheme => fl!("desktop-theme-title"),
+        Action::ReadDesktopTheme => fl!("read-desktop-theme"),
+        Action::SetAccentColor => fl!("set-accent-color"),
+        Action::ExtractPalette(Source::File) => fl!("extract-palette-file"),
+        Action::ExtractPalette(Source::Screenshot) => fl!("extract-palette-screenshot"),
+        Action::PaletteSize(size) => fl!(
+            "keybind-option",
+            group = fl!("palette"),
+            option = fl!("palette-size", size = size)
+        ),
+        Action::PickHistory => fl!("pick-history"),
+        Action::Settings => fl!("settings"),
+        Action::Keybinds => fl!("keybinds"),
+        Action::About => fl!("menu-about"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{build, format, parse, BINDABLE};
+    use crate::{app::Action, palette, sampling};
+    use std::collections::HashMap;
+
+    #[test]
+    fn round_trip() {
+        for shortcut in [
+            "Ctrl+z",
+            "Ctrl+Shift+z",
+            "Ctrl+,",
+            "Ctrl++",
+            "Alt+F5",
+            "Delete",
+        ] {
+            let key_bind = parse(shortcut).unwrap();
+            assert_eq!(format(&key_bind), shortcut);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse("Hyper+z").is_none());
+        assert!(parse("Ctrl+zz").is_none());
+        assert!(parse("").is_none());
+    }
+
+    #[test]
+    fn defaults_are_unique() {
+        let bound = BINDABLE.iter().filter(|(_, _, s)| !s.is_empty()).count();
+        assert_eq!(build(&HashMap::new()).len(), bound);
+    }
+
+    #[test]
+    fn sizes_are_bindable() {
+        let bindable = |action| BINDABLE.iter().any(|(_, a, _)| *a == action);
+
+        assert!(sampling::SIZES
+            .into_iter()
+            .all(|size| bindable(Action::SampleSize(size))));
+        assert!(palette::SIZES
+            .into_iter()
+            .all(|size| bindable(Action::PaletteSize(size))));
+    }
+
+    #[test]
+    fn overrides() {
+        let mut overrides = HashMap::new();
+        overrides.insert("undo".to_string(), String::new());
+        overrides.insert("redo".to_string(), "Ctrl+y".to_string());
+        let keybinds = build(&overrides);
+
+        assert!(!keybinds.contains_key(&parse("Ctrl+z").unwrap()));
+        assert!(keybinds.contains_key(&parse("Ctrl+y").unwrap()));
+        assert!(!keybinds.contains_key(&parse("Ctrl+Shift+z").unwrap()));
+    }
+}
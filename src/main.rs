@@ -1,13 +1,44 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use app::ColorPicker;
+use app::{ColorPicker, Flags};
 mod app;
+mod applet;
 mod colorspace;
 mod core;
+mod dbus;
+mod desktop;
+mod display;
+mod history;
+mod keybinds;
+mod named;
+mod palette;
+mod pick_history;
+mod portal;
+mod proof;
+mod sampling;
+mod settings;
 mod shaders;
+mod sheet;
+mod snap;
 mod widgets;
 
 fn main() -> cosmic::iced::Result {
+    if std::env::args().any(|arg| arg == "--applet") {
+        return applet::run();
+    }
+
+    let colors: Vec<[f32; 3]> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| {
+            let color = dbus::parse_color(&arg);
+            if color.is_none() {
+                log::warn!("Ignoring argument {arg:?}, it isn't a color");
+            }
+            color
+        })
+        .collect();
+
+    // A running instance gets the colors and is activated instead
     let settings = cosmic::app::Settings::default();
-    cosmic::app::run::<ColorPicker>(settings, ())
+    cosmic::app::run_single_instance::<ColorPicker>(settings, Flags::new(colors))
 }
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+use crate::{colorspace::rgb_to_oklab, fl};
+
+/// Maximum number of results returned by [`search`]
+pub const SEARCH_LIMIT: usize = 100;
+
+/// Every named color, parsed from the `res/named` tables.
+/// Each line of a table is `#rrggbb name`
+static COLORS: LazyLock<Vec<NamedColor>> = LazyLock::new(|| {
+    [
+        (Collection::Css, include_str!("../res/named/css.txt")),
+        (Collection::X11, include_str!("../res/named/x11.txt")),
+    ]
+    .into_iter()
+    .flat_map(|(collection, table)| parse(collection, table))
+    .collect()
+});
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collection {
+    /// The named colors of CSS Color Level 4
+    Css,
+    /// Names of X.Org's `rgb.txt` that CSS lacks or defines differently,
+    /// and its numbered variants like `snow 1` to `snow 4` and `gray 0` to `gray 100`
+    X11,
+}
+
+impl Display for Collection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Collection::Css => f.write_str(&fl!("named-css")),
+            Collection::X11 => f.write_str(&fl!("named-x11")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedColor {
+    pub name: &'static str,
+    pub collection: Collection,
+    pub rgb: [f32; 3],
+    lab: [f32; 3],
+}
+
+fn parse(collection: Collection, table: &'static str) -> impl Iterator<Item = NamedColor> {
+    table.lines().filter_map(move |line| {
+        let (hex, name) = line.trim().split_once(' ')?;
+        let srgb = hex::decode(hex.trim_start_matches('#')).unwrap_or_default();
+        let [r, g, b] = srgb[..] else {
+            log::error!("Invalid named color: {line}");
+            return None;
+        };
+        let rgb = [
+            f32::from(r) / 255.0,
+            f32::from(g) / 255.0,
+            f32::from(b) / 255.0,
+        ];
+
+        Some(NamedColor {
+            name: name.trim(),
+            collection,
+            rgb,
+            lab: rgb_to_oklab(rgb[0], rgb[1], rgb[2]),
+        })
+    })
+}
+
+pub fn all() -> &'static [NamedColor] {
+    &COLORS
+}
+
+/// Finds the named color closest to `rgb`,
+/// returns it with its ΔE (euclidean distance in OKLAB)
+pub fn nearest(rgb: [f32; 3]) -> Option<(&'static NamedColor, f32)> {
+    let lab = rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
+    all()
+        .iter()
+        .map(|color| (color, delta_e(&color.lab, &lab)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Finds named colors containing `query`, ignoring case and spaces.
+/// Names starting with `query` come first
+pub fn search(query: &str) -> Vec<&'static NamedColor> {
+    let query = normalize(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results: Vec<(bool, &NamedColor)> = all()
+        .iter()
+        .filter_map(|color| {
+            let name = normalize(color.name);
+            name.find(&query).map(|start| (start != 0, color))
+        })
+        .collect();
+    // Stable, so each collection keeps its order
+    results.sort_by_key(|(not_prefix, _)| *not_prefix);
+
+    results
+        .into_iter()
+        .take(SEARCH_LIMIT)
+        .map(|(_, color)| color)
+        .collect()
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub fn delta_e(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{all, nearest, search, Collection};
+
+    #[test]
+    fn tables_parse() {
+        let count = |collection| all().iter().filter(|c| c.collection == collection).count();
+
+        assert_eq!(count(Collection::Css), 148);
+        assert_eq!(count(Collection::X11), 425);
+    }
+
+    #[test]
+    fn nearest_exact() {
+        let (color, delta) = nearest([1.0, 99.0 / 255.0, 71.0 / 255.0]).unwrap();
+
+        assert_eq!(color.name, "tomato");
+        assert!(delta < 1e-4);
+    }
+
+    #[test]
+    fn nearest_close() {
+        let (color, delta) = nearest([0.99, 0.0, 0.01]).unwrap();
+
+        assert_eq!(color.name, "red");
+        assert!(delta > 0.0 && delta < 0.02);
+    }
+
+    #[test]
+    fn search_prefix_first() {
+        let results = search("Sky Blue");
+
+        assert!(!results.is_empty());
+        assert_eq!(results[0].name, "skyblue");
+        assert!(results.iter().any(|c| c.name == "deepskyblue"));
+        assert!(results.iter().any(|c| c.name == "lightskyblue"));
+    }
+
+    #[test]
+    fn search_empty() {
+        assert!(search("  ").is_empty());
+        assert!(search("not a color").is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::{
+    colorspace::{oklab_to_rgb, rgb_to_oklab},
+    fl, portal,
+};
+
+/// Palette sizes offered for extraction
+pub const SIZES: [usize; 4] = [4, 6, 8, 12];
+
+/// Upper bound of pixels fed into k-means, larger images are subsampled
+const MAX_SAMPLES: usize = 16384;
+const MAX_ITERATIONS: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    File,
+    Screenshot,
+}
+
+#[derive(Clone, Debug)]
+pub struct Swatch {
+    pub rgb: [f32; 3],
+    /// Fraction of the image covered by this color, 0-1
+    pub coverage: f32,
+}
+
+/// Asks the user for an image and extracts its `k` most prominent colors
+pub async fn extract_from(source: Source, k: usize) -> Option<Vec<Swatch>> {
+    let path = match source {
+        Source::File => portal::open_image(&fl!("extract-palette-title")).await?,
+        Source::Screenshot => portal::screenshot(true).await?,
+    };
+
+    let swatches = tokio::task::spawn_blocking(move || {
+        let image = portal::load_image(&path);
+        // Screenshots are temporary, the user's own images are kept
+        if source == Source::Screenshot {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove {path:?}: {e}");
+            }
+        }
+
+        Some(extract(image?.as_raw(), k))
+    })
+    .await;
+
+    match swatches {
+        Ok(swatches) => swatches,
+        Err(e) => {
+            log::error!("Failed to extract the palette: {e}");
+            None
+        }
+    }
+}
+
+/// Extracts the `k` most prominent colors of 8-bit RGBA pixels,
+/// sorted by coverage
+#[allow(clippy::cast_precision_loss)]
+pub fn extract(rgba: &[u8], k: usize) -> Vec<Swatch> {
+    let count = rgba.len() / 4;
+    let step = count.div_ceil(MAX_SAMPLES).max(1);
+
+    let samples: Vec<[f32; 3]> = rgba
+        .chunks_exact(4)
+        .step_by(step)
+        .filter(|pixel| pixel[3] > 0)
+        .map(|pixel| {
+            rgb_to_oklab(
+                f32::from(pixel[0]) / 255.0,
+                f32::from(pixel[1]) / 255.0,
+                f32::from(pixel[2]) / 255.0,
+            )
+        })
+        .collect();
+
+    let total = samples.len() as f32;
+    kmeans(&samples, k)
+        .into_iter()
+        .map(|(lab, size)| Swatch {
+            rgb: oklab_to_rgb(lab[0], lab[1], lab[2]),
+            coverage: size as f32 / total,
+        })
+        .collect()
+}
+
+/// Clusters `samples` into at most `k` groups,
+/// returns each centroid with its size sorted from largest to smallest
+#[allow(clippy::cast_precision_loss)]
+fn kmeans(samples: &[[f32; 3]], k: usize) -> Vec<([f32; 3], usize)> {
+    if samples.is_empty() || k == 0 {
+        return Vec::new();
+    }
+
+    // Deterministic farthest-point seeding, starting from the mean
+    let mut centroids = vec![mean(samples.iter())];
+    while centroids.len() < k {
+        let (farthest, distance) = samples
+            .iter()
+            .map(|sample| nearest(&centroids, sample).1)
+            .enumerate()
+            .fold(
+                (0, 0.0),
+                |best, (i, d)| if d > best.1 { (i, d) } else { best },
+            );
+
+        if distance <= f32::EPSILON {
+            break;
+        }
+        centroids.push(samples[farthest]);
+    }
+
+    let mut assignments = vec![usize::MAX; samples.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (sample, assignment) in samples.iter().zip(assignments.iter_mut()) {
+            let (cluster, _) = nearest(&centroids, sample);
+            if *assignment != cluster {
+                *assignment = cluster;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        for (cluster, centroid) in centroids.iter_mut().enumerate() {
+            let members = samples
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, a)| **a == cluster)
+                .map(|(sample, _)| sample);
+            if let Some(m) = mean_nonempty(members) {
+                *centroid = m;
+            }
+        }
+    }
+
+    let mut clusters: Vec<([f32; 3], usize)> = centroids
+        .into_iter()
+        .enumerate()
+        .map(|(cluster, centroid)| {
+            let size = assignments.iter().filter(|a| **a == cluster).count();
+            (centroid, size)
+        })
+        .filter(|(_, size)| *size > 0)
+        .collect();
+    clusters.sort_by(|a, b| b.1.cmp(&a.1));
+    clusters
+}
+
+fn nearest(centroids: &[[f32; 3]], sample: &[f32; 3]) -> (usize, f32) {
+    centroids
+        .iter()
+        .map(|centroid| distance_squared(centroid, sample))
+        .enumerate()
+        .fold(
+            (0, f32::MAX),
+            |best, (i, d)| if d < best.1 { (i, d) } else { best },
+        )
+}
+
+fn distance_squared(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn mean<'a>(samples: impl Iterator<Item = &'a [f32; 3]>) -> [f32; 3] {
+    mean_nonempty(samples).unwrap_or([0.0; 3])
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn mean_nonempty<'a>(samples: impl Iterator<Item = &'a [f32; 3]>) -> Option<[f32; 3]> {
+    let mut sum = [0.0; 3];
+    let mut count = 0usize;
+    for sample in samples {
+        sum[0] += sample[0];
+        sum[1] += sample[1];
+        sum[2] += sample[2];
+        count += 1;
+    }
+
+    (count > 0).then(|| {
+        let count = count as f32;
+        [sum[0] / count, sum[1] / count, sum[2] / count]
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::extract;
+    use crate::colorspace::test::aprox_eq_within;
+
+    fn image(pixels: &[([u8; 3], usize)]) -> Vec<u8> {
+        let mut rgba = Vec::new();
+        for (pixel, count) in pixels {
+            for _ in 0..*count {
+                rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+            }
+        }
+        rgba
+    }
+
+    #[test]
+    fn two_colors() {
+        let rgba = image(&[([255, 0, 0], 75), ([0, 0, 255], 25)]);
+        let palette = extract(&rgba, 2);
+
+        assert_eq!(palette.len(), 2);
+        assert!(aprox_eq_within(&palette[0].rgb, &[1.0, 0.0, 0.0], 1e-3));
+        assert!((palette[0].coverage - 0.75).abs() < 1e-4);
+        assert!(aprox_eq_within(&palette[1].rgb, &[0.0, 0.0, 1.0], 1e-3));
+        assert!((palette[1].coverage - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn fewer_colors_than_k() {
+        let rgba = image(&[([0, 255, 0], 10)]);
+        let palette = extract(&rgba, 6);
+
+        assert_eq!(palette.len(), 1);
+        assert!(aprox_eq_within(&palette[0].rgb, &[0.0, 1.0, 0.0], 1e-3));
+        assert!((palette[0].coverage - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn transparent_pixels_are_ignored() {
+        let mut rgba = image(&[([255, 255, 255], 4)]);
+        rgba.extend_from_slice(&[0, 0, 0, 0]);
+        let palette = extract(&rgba, 2);
+
+        assert_eq!(palette.len(), 1);
+        assert!(aprox_eq_within(&palette[0].rgb, &[1.0; 3], 1e-3));
+    }
+
+    #[test]
+    fn empty() {
+        assert!(extract(&[], 4).is_empty());
+    }
+}
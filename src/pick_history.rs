@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::CopyFormat;
+use crate::colorspace::ColorSpaceCombo;
+
+/// Maximum number of entries kept in the history
+pub const LIMIT: usize = 50;
+
+const KEY: &str = "pick_history";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryKind {
+    Picked,
+    Copied,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    pub kind: EntryKind,
+    pub rgb: [f32; 3],
+    pub space: ColorSpaceCombo,
+    /// Format of `text`, picks use the space's own format
+    #[serde(default)]
+    pub format: CopyFormat,
+    /// The color as it was formatted at the time
+    pub text: String,
+}
+
+impl Entry {
+    pub fn new(
+        kind: EntryKind,
+        rgb: [f32; 3],
+        space: ColorSpaceCombo,
+        format: CopyFormat,
+        text: String,
+    ) -> Self {
+        Self {
+            timestamp: now(),
+            kind,
+            rgb,
+            space,
+            format,
+            text,
+        }
+    }
+
+    /// Seconds elapsed since this entry was recorded
+    pub fn age(&self) -> u64 {
+        now().saturating_sub(self.timestamp)
+    }
+}
+
+/// Picked and copied colors, newest first
+#[derive(Default)]
+pub struct PickHistory {
+    pub entries: VecDeque<Entry>,
+}
+
+impl PickHistory {
+    pub fn load(state: &cosmic::cosmic_config::Config) -> Self {
+        use cosmic::cosmic_config::ConfigGet;
+
+        match state.get::<VecDeque<Entry>>(KEY) {
+            Ok(mut entries) => {
+                entries.truncate(LIMIT);
+                Self { entries }
+            }
+            Err(e) => {
+                log::info!("No pick history loaded: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, state: &cosmic::cosmic_config::Config) {
+        use cosmic::cosmic_config::ConfigSet;
+
+        if let Err(e) = state.set(KEY, &self.entries) {
+            log::error!("Failed to save pick history: {e}");
+        }
+    }
+
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push_front(entry);
+        self.entries.truncate(LIMIT);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.entries.remove(index);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Entry, EntryKind, PickHistory, LIMIT};
+    use crate::app::CopyFormat;
+    use crate::colorspace::ColorSpaceCombo;
+
+    #[test]
+    fn newest_first() {
+        let mut history = PickHistory::default();
+        history.push(Entry::new(
+            EntryKind::Picked,
+            [1.0, 0.0, 0.0],
+            ColorSpaceCombo::Rgb,
+            CopyFormat::Current,
+            "1, 0, 0".into(),
+        ));
+        history.push(Entry::new(
+            EntryKind::Copied,
+            [0.0, 1.0, 0.0],
+            ColorSpaceCombo::Hsv,
+            CopyFormat::Hex,
+            "#00ff00".into(),
+        ));
+
+        assert_eq!(history.entries[0].kind, EntryKind::Copied);
+        assert_eq!(history.entries[1].kind, EntryKind::Picked);
+    }
+
+    #[test]
+    fn limit() {
+        let mut history = PickHistory::default();
+        for _ in 0..LIMIT * 2 {
+            history.push(Entry::new(
+                EntryKind::Picked,
+                [0.0; 3],
+                ColorSpaceCombo::Rgb,
+                CopyFormat::Current,
+                String::new(),
+            ));
+        }
+
+        assert_eq!(history.entries.len(), LIMIT);
+    }
+}
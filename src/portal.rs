@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+
+use ashpd::desktop::{
+    file_chooser::{FileFilter, SelectedFiles},
+    screenshot::Screenshot,
+    Color,
+};
+use image::RgbaImage;
+
+/// Asks the user to pick a color from the screen
+pub async fn pick_color() -> Option<[f32; 3]> {
+    let request = Color::pick().send().await;
+    let Ok(request) = request else {
+        log::error!("{request:?}");
+        return None;
+    };
+
+    let result = request.response();
+    let Ok(color) = result else {
+        log::error!("{result:?}");
+        return None;
+    };
+
+    #[allow(clippy::cast_possible_truncation)]
+    Some([
+        color.red() as f32,
+        color.green() as f32,
+        color.blue() as f32,
+    ])
+}
+
+/// Takes a screenshot, letting the user choose an area when `interactive`
+pub async fn screenshot(interactive: bool) -> Option<PathBuf> {
+    let request = Screenshot::request()
+        .interactive(interactive)
+        .modal(true)
+        .send()
+        .await;
+    let Ok(request) = request else {
+        log::error!("{request:?}");
+        return None;
+    };
+
+    let result = request.response();
+    let Ok(screenshot) = result else {
+        log::error!("{result:?}");
+        return None;
+    };
+
+    let Ok(path) = screenshot.uri().to_file_path() else {
+        log::error!("Screenshot is not a local file: {}", screenshot.uri());
+        return None;
+    };
+
+    Some(path)
+}
+
+/// Asks the user for an image file
+pub async fn open_image(title: &str) -> Option<PathBuf> {
+    open_file(
+        title,
+        FileFilter::new("Images")
+            .mimetype("image/png")
+            .mimetype("image/jpeg"),
+    )
+    .await
+}
+
+/// Asks the user for a file matching `filter`
+pub async fn open_file(title: &str, filter: FileFilter) -> Option<PathBuf> {
+    let request = SelectedFiles::open_file()
+        .title(title)
+        .modal(true)
+        .filter(filter)
+        .send()
+        .await;
+    let Ok(request) = request else {
+        log::error!("{request:?}");
+        return None;
+    };
+
+    let result = request.response();
+    let Ok(files) = result else {
+        log::error!("{result:?}");
+        return None;
+    };
+
+    let uri = files.uris().first()?;
+    let Ok(path) = uri.to_file_path() else {
+        log::error!("{uri} is not a local file");
+        return None;
+    };
+
+    Some(path)
+}
+
+/// Asks the user where to save a file named `name`
+pub async fn save_file(title: &str, name: &str, filter: FileFilter) -> Option<PathBuf> {
+    let request = SelectedFiles::save_file()
+        .title(title)
+        .current_name(name)
+        .modal(true)
+        .filter(filter)
+        .send()
+        .await;
+    let Ok(request) = request else {
+        log::error!("{request:?}");
+        return None;
+    };
+
+    let result = request.response();
+    let Ok(files) = result else {
+        log::error!("{result:?}");
+        return None;
+    };
+
+    let uri = files.uris().first()?;
+    let Ok(path) = uri.to_file_path() else {
+        log::error!("{uri} is not a local file");
+        return None;
+    };
+
+    Some(path)
+}
+
+pub fn load_image(path: &Path) -> Option<RgbaImage> {
+    match image::open(path) {
+        Ok(image) => Some(image.to_rgba8()),
+        Err(e) => {
+            log::error!("Failed to open {path:?}: {e}");
+            None
+        }
+    }
+}
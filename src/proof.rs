@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    colorspace::{rgb_to_oklab, Cmyk, Conversion},
+    fl,
+    named::delta_e,
+};
+
+/// Points per axis of the gamut mask sent to the graph shaders
+const GRID: usize = 8;
+
+/// ΔE in OKLAB above which a color doesn't reproduce on the target
+const OUT_OF_GAMUT: f32 = 0.02;
+
+/// Whether each point of a `GRID`³ lattice over the RGB cube is in gamut,
+/// packed in fours like a WGSL `array<vec4<f32>, 128>`
+pub type Gamut = [[f32; 4]; GRID * GRID * GRID / 4];
+
+/// Where colors are meant to be reproduced
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    /// The CMYK profile of the settings, or the naive CMYK conversion
+    #[default]
+    Cmyk,
+    Srgb,
+    /// The 216 colors of the web-safe palette
+    WebSafe,
+    /// The 6×6×6 cube and 24 grays of 256-color terminals, whose first 16 colors
+    /// depend on the terminal's theme
+    Xterm,
+}
+
+impl Target {
+    pub const ALL: [Target; 4] = [Target::Cmyk, Target::Srgb, Target::WebSafe, Target::Xterm];
+
+    /// Mode of the `soft_proof` shader function
+    fn shader_mode(self) -> u32 {
+        match self {
+            Target::Cmyk => 1,
+            Target::Srgb => 2,
+            Target::WebSafe => 3,
+            Target::Xterm => 4,
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Cmyk => f.write_str(&fl!("proof-cmyk")),
+            Target::Srgb => f.write_str("sRGB"),
+            Target::WebSafe => f.write_str(&fl!("proof-web-safe")),
+            Target::Xterm => f.write_str(&fl!("proof-xterm")),
+        }
+    }
+}
+
+/// How `rgb` reproduces on `target`
+pub fn apply(target: Target, rgb: [f32; 3], conversion: &Conversion) -> [f32; 3] {
+    let rgb = rgb.map(|c| c.clamp(0.0, 1.0));
+    match target {
+        Target::Cmyk => Cmyk::from_rgb(rgb, conversion.cmyk_profile.clone()).to_rgb(),
+        Target::Srgb => rgb,
+        Target::WebSafe => rgb.map(|c| (c * 5.0).round() / 5.0),
+        Target::Xterm => xterm(rgb),
+    }
+}
+
+/// Whether `rgb` reproduces on `target` without a visible difference
+pub fn in_gamut(target: Target, rgb: [f32; 3], conversion: &Conversion) -> bool {
+    let proofed = apply(target, rgb, conversion);
+    let a = rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
+    let b = rgb_to_oklab(proofed[0], proofed[1], proofed[2]);
+
+    delta_e(&a, &b) <= OUT_OF_GAMUT
+}
+
+/// Target proofed in the graphs with its gamut mask
+pub struct Proof {
+    target: Target,
+    gamut: Gamut,
+}
+
+impl Proof {
+    /// Proofs `target`, make a new one when the CMYK profile changes
+    pub fn new(target: Target, conversion: &Conversion) -> Self {
+        Self {
+            target,
+            gamut: gamut(target, conversion),
+        }
+    }
+}
+
+/// Mode and gamut mask of the `soft_proof` shader function, `None` turns it off
+pub fn shader(proof: Option<&Proof>) -> (u32, Gamut) {
+    proof.map_or((0, [[1.0; 4]; GRID * GRID * GRID / 4]), |proof| {
+        (proof.target.shader_mode(), proof.gamut)
+    })
+}
+
+fn gamut(target: Target, conversion: &Conversion) -> Gamut {
+    let mut gamut = [[1.0; 4]; GRID * GRID * GRID / 4];
+    // Palettes are quantized in the shader instead
+    if target != Target::Cmyk {
+        return gamut;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let step = |i: usize| i as f32 / (GRID - 1) as f32;
+    for index in 0..GRID * GRID * GRID {
+        let rgb = [
+            step(index % GRID),
+            step(index / GRID % GRID),
+            step(index / (GRID * GRID)),
+        ];
+        if !in_gamut(target, rgb, conversion) {
+            gamut[index / 4][index % 4] = 0.0;
+        }
+    }
+
+    gamut
+}
+
+/// Nearest color of the cube or the gray ramp, like `xterm` in `proof.wgsl`
+fn xterm(rgb: [f32; 3]) -> [f32; 3] {
+    let cube = rgb.map(|c| {
+        let c = c * 255.0;
+        if c < 48.0 {
+            0.0
+        } else {
+            ((c - 55.0) / 40.0).round().clamp(1.0, 5.0) * 40.0 + 55.0
+        }
+    });
+    let average = (rgb[0] + rgb[1] + rgb[2]) / 3.0 * 255.0;
+    let gray = [((average - 8.0) / 10.0).round().clamp(0.0, 23.0) * 10.0 + 8.0; 3];
+
+    let distance = |a: [f32; 3]| {
+        a.iter()
+            .zip(rgb)
+            .map(|(a, b)| (a / 255.0 - b).powi(2))
+            .sum::<f32>()
+    };
+    let nearest = if distance(gray) < distance(cube) {
+        gray
+    } else {
+        cube
+    };
+
+    nearest.map(|c| c / 255.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply, in_gamut, Target};
+    use crate::colorspace::{test::aprox_eq, Conversion};
+
+    #[test]
+    fn srgb() {
+        assert!(aprox_eq(
+            &apply(Target::Srgb, [1.2, 0.5, -0.1], &Conversion::default()),
+            &[1.0, 0.5, 0.0]
+        ));
+        assert!(in_gamut(
+            Target::Srgb,
+            [0.2, 0.4, 0.6],
+            &Conversion::default()
+        ));
+        assert!(!in_gamut(
+            Target::Srgb,
+            [1.2, 0.5, -0.1],
+            &Conversion::default()
+        ));
+    }
+
+    #[test]
+    fn naive_cmyk() {
+        // Without a profile CMYK reproduces sRGB
+        assert!(aprox_eq(
+            &apply(Target::Cmyk, [0.25, 0.5, 0.75], &Conversion::default()),
+            &[0.25, 0.5, 0.75]
+        ));
+        assert!(aprox_eq(
+            &apply(Target::Cmyk, [1.2, 0.5, 0.0], &Conversion::default()),
+            &[1.0, 0.5, 0.0]
+        ));
+    }
+
+    #[test]
+    fn web_safe() {
+        assert!(aprox_eq(
+            &apply(Target::WebSafe, [0.0, 0.55, 0.95], &Conversion::default()),
+            &[0.0, 0.6, 1.0]
+        ));
+        assert!(in_gamut(
+            Target::WebSafe,
+            [0.2, 0.4, 0.8],
+            &Conversion::default()
+        ));
+        assert!(!in_gamut(
+            Target::WebSafe,
+            [0.3, 0.4, 0.8],
+            &Conversion::default()
+        ));
+    }
+
+    #[test]
+    fn xterm() {
+        let rgb = |r: u8, g: u8, b: u8| [r, g, b].map(|c| f32::from(c) / 255.0);
+
+        // Colors of the palette are kept
+        for color in [rgb(0, 95, 135), rgb(255, 215, 0), rgb(238, 238, 238)] {
+            assert!(aprox_eq(
+                &apply(Target::Xterm, color, &Conversion::default()),
+                &color
+            ));
+        }
+        assert!(aprox_eq(
+            &apply(Target::Xterm, rgb(250, 100, 40), &Conversion::default()),
+            &rgb(255, 95, 0)
+        ));
+        assert!(aprox_eq(
+            &apply(Target::Xterm, rgb(120, 121, 119), &Conversion::default()),
+            &rgb(118, 118, 118)
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::{fl, portal};
+
+/// Sizes offered for the sampling area, in pixels
+pub const SIZES: [u32; 4] = [3, 5, 11, 21];
+
+/// Radius of the loupe preview around the sampled point, in pixels
+pub const LOUPE_RADIUS: u32 = 12;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SampleMode {
+    /// Single pixel from the color picker portal
+    #[default]
+    Pixel,
+    Average,
+    Median,
+    Dominant,
+}
+
+impl SampleMode {
+    pub const ALL: [SampleMode; 4] = [
+        SampleMode::Pixel,
+        SampleMode::Average,
+        SampleMode::Median,
+        SampleMode::Dominant,
+    ];
+}
+
+impl Display for SampleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleMode::Pixel => f.write_str(&fl!("sample-pixel")),
+            SampleMode::Average => f.write_str(&fl!("sample-average")),
+            SampleMode::Median => f.write_str(&fl!("sample-median")),
+            SampleMode::Dominant => f.write_str(&fl!("sample-dominant")),
+        }
+    }
+}
+
+/// The result of sampling an area of the screen
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub rgb: [f32; 3],
+    /// `(width, height, rgba)` of the area around the sampled point
+    pub loupe: (u32, u32, Vec<u8>),
+}
+
+/// Asks the user to select a region of the screen and samples the area around its center
+pub async fn pick_area(mode: SampleMode, size: u32) -> Option<Sample> {
+    let path = portal::screenshot(true).await?;
+
+    let sample = tokio::task::spawn_blocking(move || {
+        let buffer = portal::load_image(&path);
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Failed to remove {path:?}: {e}");
+        }
+        let buffer = buffer?;
+
+        let image = Image {
+            width: buffer.width(),
+            height: buffer.height(),
+            rgba: buffer.as_raw(),
+        };
+        if image.width == 0 || image.height == 0 {
+            return None;
+        }
+        let point = (image.width / 2, image.height / 2);
+
+        Some(Sample {
+            rgb: image.sample(point, size, mode),
+            loupe: image.loupe(point, LOUPE_RADIUS),
+        })
+    })
+    .await;
+
+    match sample {
+        Ok(sample) => sample,
+        Err(e) => {
+            log::error!("Failed to sample the screenshot: {e}");
+            None
+        }
+    }
+}
+
+/// An 8-bit RGBA image
+pub struct Image<'a> {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: &'a [u8],
+}
+
+impl Image<'_> {
+    fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = (y * self.width + x) as usize * 4;
+        [self.rgba[i], self.rgba[i + 1], self.rgba[i + 2]]
+    }
+
+    /// Pixels of the `size`x`size` area around `(x, y)`, clipped to the image
+    fn area(&self, (x, y): (u32, u32), size: u32) -> Vec<[u8; 3]> {
+        let half = size / 2;
+        let x0 = x.saturating_sub(half);
+        let y0 = y.saturating_sub(half);
+        let x1 = (x + half).min(self.width.saturating_sub(1));
+        let y1 = (y + half).min(self.height.saturating_sub(1));
+
+        let mut pixels = Vec::with_capacity((size * size) as usize);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                pixels.push(self.pixel(x, y));
+            }
+        }
+        pixels
+    }
+
+    /// Samples the `size`x`size` area around `point` using `mode`
+    pub fn sample(&self, point: (u32, u32), size: u32, mode: SampleMode) -> [f32; 3] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 3];
+        }
+
+        let pixels = match mode {
+            SampleMode::Pixel => vec![self.pixel(point.0, point.1)],
+            _ => self.area(point, size),
+        };
+
+        match mode {
+            SampleMode::Pixel | SampleMode::Average => average(&pixels),
+            SampleMode::Median => median(&pixels),
+            SampleMode::Dominant => dominant(&pixels),
+        }
+    }
+
+    /// Crops the area around `point` for the loupe preview,
+    /// returns `(width, height, rgba)`
+    pub fn loupe(&self, (x, y): (u32, u32), radius: u32) -> (u32, u32, Vec<u8>) {
+        let x0 = x.saturating_sub(radius);
+        let y0 = y.saturating_sub(radius);
+        let x1 = (x + radius + 1).min(self.width);
+        let y1 = (y + radius + 1).min(self.height);
+
+        let mut rgba = Vec::with_capacity(((x1 - x0) * (y1 - y0) * 4) as usize);
+        for y in y0..y1 {
+            let start = (y * self.width + x0) as usize * 4;
+            let end = (y * self.width + x1) as usize * 4;
+            rgba.extend_from_slice(&self.rgba[start..end]);
+        }
+
+        (x1 - x0, y1 - y0, rgba)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn average(pixels: &[[u8; 3]]) -> [f32; 3] {
+    let mut sum = [0u32; 3];
+    for pixel in pixels {
+        sum[0] += u32::from(pixel[0]);
+        sum[1] += u32::from(pixel[1]);
+        sum[2] += u32::from(pixel[2]);
+    }
+
+    let count = pixels.len().max(1) as f32 * 255.0;
+    [
+        sum[0] as f32 / count,
+        sum[1] as f32 / count,
+        sum[2] as f32 / count,
+    ]
+}
+
+/// Per-channel median
+fn median(pixels: &[[u8; 3]]) -> [f32; 3] {
+    let mut rgb = [0.0; 3];
+    for (channel, value) in rgb.iter_mut().enumerate() {
+        let mut values: Vec<u8> = pixels.iter().map(|p| p[channel]).collect();
+        values.sort_unstable();
+        *value = f32::from(values[values.len() / 2]) / 255.0;
+    }
+    rgb
+}
+
+/// Average of the most common bucket when quantized to 4 bits per channel
+fn dominant(pixels: &[[u8; 3]]) -> [f32; 3] {
+    let mut buckets: HashMap<[u8; 3], Vec<[u8; 3]>> = HashMap::new();
+    for pixel in pixels {
+        buckets
+            .entry([pixel[0] >> 4, pixel[1] >> 4, pixel[2] >> 4])
+            .or_default()
+            .push(*pixel);
+    }
+
+    buckets
+        .into_values()
+        .max_by_key(Vec::len)
+        .map_or([0.0; 3], |bucket| average(&bucket))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Image, SampleMode};
+    use crate::colorspace::test::aprox_eq;
+
+    /// 3x3 pixels, white except for a black center pixel
+    fn pixels() -> Vec<u8> {
+        let mut rgba = vec![255u8; 3 * 3 * 4];
+        rgba[16..19].copy_from_slice(&[0, 0, 0]);
+        rgba
+    }
+
+    fn image(rgba: &[u8]) -> Image<'_> {
+        Image {
+            width: 3,
+            height: 3,
+            rgba,
+        }
+    }
+
+    #[test]
+    fn pixel() {
+        let rgba = pixels();
+        let rgb = image(&rgba).sample((1, 1), 3, SampleMode::Pixel);
+        assert!(aprox_eq(&rgb, &[0.0; 3]));
+    }
+
+    #[test]
+    fn average() {
+        let rgba = pixels();
+        let rgb = image(&rgba).sample((1, 1), 3, SampleMode::Average);
+        assert!(aprox_eq(&rgb, &[8.0 / 9.0; 3]));
+    }
+
+    #[test]
+    fn median() {
+        let rgba = pixels();
+        let rgb = image(&rgba).sample((1, 1), 3, SampleMode::Median);
+        assert!(aprox_eq(&rgb, &[1.0; 3]));
+    }
+
+    #[test]
+    fn dominant() {
+        let rgba = pixels();
+        let rgb = image(&rgba).sample((1, 1), 3, SampleMode::Dominant);
+        assert!(aprox_eq(&rgb, &[1.0; 3]));
+    }
+
+    #[test]
+    fn clipped() {
+        let rgba = pixels();
+        let image = image(&rgba);
+
+        // Only the top-left 2x2 pixels are inside the image
+        let rgb = image.sample((0, 0), 3, SampleMode::Average);
+        assert!(aprox_eq(&rgb, &[0.75; 3]));
+
+        let (width, height, loupe) = image.loupe((0, 0), 1);
+        assert_eq!((width, height, loupe.len()), (2, 2, 16));
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use cosmic::cosmic_config::{self, ConfigGet, ConfigSet};
+use cosmic::theme;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::CopyFormat,
+    colorspace::{CmykProfile, ColorSpaceCombo, Context, Conversion, Intent, Unit},
+    fl,
+    proof::Target,
+    snap::Palette,
+};
+
+const KEY: &str = "settings";
+
+/// Decimal places offered for channel fields, `None` shows as many as needed
+pub const PRECISIONS: [Option<u32>; 5] = [None, Some(2), Some(3), Some(4), Some(6)];
+
+/// Slider scroll steps offered, as a fraction of the slider's range
+pub const SCROLL_STEPS: [f32; 5] = [0.001, 0.005, 0.01, 0.02, 0.05];
+
+/// Total ink coverage limits offered, in percent
+pub const INK_LIMITS: [u32; 6] = [240, 260, 280, 300, 320, 340];
+
+/// Luminances offered for the SDR white, in cd/m²
+pub const SDR_WHITES: [u32; 4] = [80, 100, 203, 300];
+
+/// Peak luminances offered for tone-mapped previews, in cd/m²
+pub const HDR_PEAKS: [u32; 5] = [400, 600, 1000, 4000, 10000];
+
+pub const COPY_FORMATS: [CopyFormat; 8] = [
+    CopyFormat::Current,
+    CopyFormat::Hex,
+    CopyFormat::Space(ColorSpaceCombo::Rgb),
+    CopyFormat::Space(ColorSpaceCombo::Hsv),
+    CopyFormat::Space(ColorSpaceCombo::Oklab),
+    CopyFormat::Space(ColorSpaceCombo::Oklch),
+    CopyFormat::Space(ColorSpaceCombo::Cmyk),
+    CopyFormat::Space(ColorSpaceCombo::Hdr),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppTheme {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl AppTheme {
+    pub const ALL: [AppTheme; 3] = [AppTheme::System, AppTheme::Dark, AppTheme::Light];
+
+    pub fn theme(self) -> theme::Theme {
+        match self {
+            AppTheme::System => theme::system_preference(),
+            AppTheme::Dark => theme::system_dark(),
+            AppTheme::Light => theme::system_light(),
+        }
+    }
+}
+
+impl Display for AppTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppTheme::System => f.write_str(&fl!("theme-system")),
+            AppTheme::Dark => f.write_str(&fl!("theme-dark")),
+            AppTheme::Light => f.write_str(&fl!("theme-light")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Color space of new columns
+    pub default_space: ColorSpaceCombo,
+    /// Decimal places shown in channel fields
+    pub precision: Option<u32>,
+    /// How 0-1 channels are shown and typed
+    pub unit: Unit,
+    /// Format used by the copy button and the copy shortcut
+    pub copy_format: CopyFormat,
+    /// Fraction of a slider's range moved per scroll step
+    pub scroll_step: f32,
+    pub show_graphs: bool,
+    pub expanded: bool,
+    pub app_theme: AppTheme,
+    /// Small window with only the active column's swatch, hex and buttons
+    pub compact: bool,
+    /// Keeps the compact window above other windows
+    pub always_on_top: bool,
+    /// ICC output profile of CMYK columns, `None` uses the naive formula
+    pub cmyk_profile: Option<PathBuf>,
+    pub rendering_intent: Intent,
+    /// Total ink coverage in percent above which CMYK columns show a warning
+    pub ink_limit: u32,
+    /// Shows swatches, sliders and graphs through the display profile
+    pub color_managed: bool,
+    /// ICC profile of the monitor, `None` asks colord
+    pub display_profile: Option<PathBuf>,
+    /// Shows swatches and graphs as they reproduce on `proof_target`
+    pub soft_proof: bool,
+    pub proof_target: Target,
+    /// Palette whose nearest entry is shown under each column, `None` hides it
+    pub snap_palette: Option<Palette>,
+    /// GIMP or hex list palette offered for snapping
+    pub imported_palette: Option<PathBuf>,
+    /// Shows the luminance of columns and tone-mapped previews
+    pub hdr: bool,
+    /// Luminance in cd/m² of an RGB value of 1
+    pub sdr_white: u32,
+    /// Luminance in cd/m² shown as white by tone-mapped previews
+    pub hdr_peak: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            default_space: ColorSpaceCombo::default(),
+            precision: None,
+            unit: Unit::default(),
+            copy_format: CopyFormat::Current,
+            scroll_step: 0.01,
+            show_graphs: false,
+            expanded: false,
+            app_theme: AppTheme::default(),
+            compact: false,
+            always_on_top: false,
+            cmyk_profile: None,
+            rendering_intent: Intent::default(),
+            ink_limit: 300,
+            color_managed: false,
+            display_profile: None,
+            soft_proof: false,
+            proof_target: Target::default(),
+            snap_palette: None,
+            imported_palette: None,
+            hdr: false,
+            sdr_white: 203,
+            hdr_peak: 1000,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(config: &cosmic_config::Config) -> Self {
+        match config.get::<Settings>(KEY) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::info!("No settings loaded: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// How color space channels are shown and edited, without the display profile and proof
+    pub fn context(&self) -> Context {
+        Context {
+            precision: self.precision,
+            unit: self.unit,
+            scroll_step: self.scroll_step,
+            ink_limit: self.ink_limit,
+            display: None,
+            proof: None,
+        }
+    }
+
+    /// What conversions from RGB depend on, loading the CMYK profile
+    pub fn conversion(&mut self) -> Conversion {
+        Conversion {
+            cmyk_profile: self.load_cmyk_profile(),
+            sdr_white: self.sdr_white,
+        }
+    }
+
+    /// Loads the CMYK profile, forgetting its path when it fails to load
+    pub fn load_cmyk_profile(&mut self) -> Option<Rc<CmykProfile>> {
+        let profile = self
+            .cmyk_profile
+            .as_deref()
+            .and_then(|path| CmykProfile::load(path, self.rendering_intent));
+        if profile.is_none() {
+            self.cmyk_profile = None;
+        }
+        profile.map(Rc::new)
+    }
+
+    pub fn save(&self, config: &cosmic_config::Config) {
+        if let Err(e) = config.set(KEY, self) {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+}
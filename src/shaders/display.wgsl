@@ -0,0 +1,17 @@
+// Maps an sRGB color to the monitor, `display` converts linear sRGB to the linear display
+fn to_display(display: mat3x3<f32>, rgb: vec3<f32>) -> vec3<f32> {
+    return srgb_from_linear(display * srgb_to_linear(rgb));
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let a = abs(c);
+    let linear = select(pow((a + 0.055) / 1.055, vec3<f32>(2.4)), a / 12.92, a <= vec3<f32>(0.04045));
+    return sign(c) * linear;
+}
+
+fn srgb_from_linear(c: vec3<f32>) -> vec3<f32> {
+    let a = abs(c);
+    let srgb = select(1.055 * pow(a, vec3<f32>(1.0 / 2.4)) - 0.055, a * 12.92, a <= vec3<f32>(0.0031308));
+    return sign(c) * srgb;
+}
+
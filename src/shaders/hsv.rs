@@ -4,13 +4,14 @@ use cosmic::iced::{
     Rectangle,
 };
 
-use crate::shaders::ShaderPipeline;
+use crate::shaders::{Preview, ShaderPipeline};
 
 // ---- Shader ----
 pub struct ColorGraph {
     pub hue: f32,
     pub saturation: f32,
     pub value: f32,
+    pub preview: Preview,
 }
 
 impl<Message> shader::Program<Message> for ColorGraph {
@@ -23,7 +24,7 @@ impl<Message> shader::Program<Message> for ColorGraph {
         _cursor: cosmic::iced::mouse::Cursor,
         _bounds: cosmic::iced::Rectangle,
     ) -> Self::Primitive {
-        Primitive::new(self.hue, self.saturation, self.value)
+        Primitive::new(self.hue, self.saturation, self.value, &self.preview)
     }
 }
 
@@ -33,12 +34,17 @@ pub struct Primitive {
 }
 
 impl Primitive {
-    pub fn new(hue: f32, saturation: f32, value: f32) -> Self {
+    pub fn new(hue: f32, saturation: f32, value: f32, preview: &Preview) -> Self {
         Self {
             uniforms: Uniforms {
                 hue,
                 saturation,
                 value,
+                _padding: 0.0,
+                display: preview.display,
+                proof: preview.proof,
+                _proof_padding: [0; 3],
+                gamut: preview.gamut,
             },
         }
     }
@@ -55,7 +61,15 @@ impl shader::Primitive for Primitive {
         _bounds: &Rectangle,
         _viewport: &Viewport,
     ) {
-        pipeline.initialize(device, queue, include_str!("hsv.wgsl"));
+        pipeline.initialize(
+            device,
+            queue,
+            concat!(
+                include_str!("display.wgsl"),
+                include_str!("proof.wgsl"),
+                include_str!("hsv.wgsl")
+            ),
+        );
         pipeline.write(queue, &self.uniforms);
     }
 
@@ -76,4 +90,10 @@ pub struct Uniforms {
     hue: f32,
     saturation: f32,
     value: f32,
+    _padding: f32,
+    /// Columns of a `mat3x3`, padded to 16 bytes
+    display: [[f32; 4]; 3],
+    proof: u32,
+    _proof_padding: [u32; 3],
+    gamut: crate::proof::Gamut,
 }
@@ -2,6 +2,9 @@ struct HSV {
     hue: f32,
     saturation: f32,
     value: f32,
+    display: mat3x3<f32>,
+    proof: u32,
+    gamut: array<vec4<f32>, 128>,
 }
 
 @group(0) @binding(0) var<uniform> hsv: HSV;
@@ -43,6 +46,6 @@ fn fs_main(
     }
 
     let m = uv.y - c;
-    let color = vec4<f32>(r1 + m, g1 + m, b1 + m, 1.0);
-    return color;
+    let color = soft_proof(hsv.proof, hsv.gamut, vec4<f32>(r1 + m, g1 + m, b1 + m, 1.0));
+    return vec4<f32>(to_display(hsv.display, color.xyz), color.w);
 }
@@ -6,6 +6,30 @@ use std::marker::PhantomData;
 
 use cosmic::iced::{wgpu, widget, Rectangle};
 
+use crate::{colorspace::Context, display, proof};
+
+/// How the graphs show colors, through the display profile and soft proofed
+#[derive(Clone, Copy)]
+pub struct Preview {
+    /// Matrix of the display profile, see [`display::matrix`]
+    pub display: [[f32; 4]; 3],
+    /// Mode of the `soft_proof` shader function, 0 when soft proofing is off
+    pub proof: u32,
+    /// Gamut mask of the `soft_proof` shader function
+    pub gamut: proof::Gamut,
+}
+
+impl Preview {
+    pub fn new(ctx: &Context) -> Self {
+        let (proof, gamut) = proof::shader(ctx.proof.as_deref());
+        Self {
+            display: display::matrix(ctx.display.as_deref()),
+            proof,
+            gamut,
+        }
+    }
+}
+
 pub struct ShaderPipeline<T, const ID: u32> {
     pipeline: Option<wgpu::RenderPipeline>,
     pipeline_layout: wgpu::PipelineLayout,
@@ -4,13 +4,14 @@ use cosmic::iced::{
     Rectangle,
 };
 
-use crate::shaders::ShaderPipeline;
+use crate::shaders::{Preview, ShaderPipeline};
 
 // ---- Shader ----
 pub struct ColorGraph<const MODE: u32> {
     pub lightness: f32,
     pub green_red: f32,
     pub blue_yellow: f32,
+    pub preview: Preview,
 }
 
 impl<const M: u32, Message> shader::Program<Message> for ColorGraph<M> {
@@ -23,7 +24,12 @@ impl<const M: u32, Message> shader::Program<Message> for ColorGraph<M> {
         _cursor: cosmic::iced::mouse::Cursor,
         _bounds: cosmic::iced::Rectangle,
     ) -> Self::Primitive {
-        Primitive::<M>::new(self.lightness, self.green_red, self.blue_yellow)
+        Primitive::<M>::new(
+            self.lightness,
+            self.green_red,
+            self.blue_yellow,
+            &self.preview,
+        )
     }
 }
 
@@ -33,13 +39,17 @@ pub struct Primitive<const M: u32> {
 }
 
 impl<const M: u32> Primitive<M> {
-    pub fn new(lightness: f32, green_red: f32, blue_yellow: f32) -> Self {
+    pub fn new(lightness: f32, green_red: f32, blue_yellow: f32, preview: &Preview) -> Self {
         Self {
             uniforms: Uniforms {
                 lightness,
                 green_red,
                 blue_yellow,
                 mode: M,
+                display: preview.display,
+                proof: preview.proof,
+                _proof_padding: [0; 3],
+                gamut: preview.gamut,
             },
         }
     }
@@ -56,7 +66,15 @@ impl<const M: u32> shader::Primitive for Primitive<M> {
         _bounds: &Rectangle,
         _viewport: &Viewport,
     ) {
-        pipeline.initialize(device, queue, include_str!("oklab.wgsl"));
+        pipeline.initialize(
+            device,
+            queue,
+            concat!(
+                include_str!("display.wgsl"),
+                include_str!("proof.wgsl"),
+                include_str!("oklab.wgsl")
+            ),
+        );
         pipeline.write(queue, &self.uniforms);
     }
 
@@ -78,4 +96,9 @@ pub struct Uniforms {
     green_red: f32,
     blue_yellow: f32,
     mode: u32,
+    /// Columns of a `mat3x3`, padded to 16 bytes
+    display: [[f32; 4]; 3],
+    proof: u32,
+    _proof_padding: [u32; 3],
+    gamut: crate::proof::Gamut,
 }
@@ -3,6 +3,9 @@ struct OKLCH {
     chroma: f32,
     hue: f32,
     mode: u32,
+    display: mat3x3<f32>,
+    proof: u32,
+    gamut: array<vec4<f32>, 128>,
 }
 
 const MODE_LIGHTNESS = 0u;
@@ -43,7 +46,8 @@ fn fs_main(
     if max(color.x, max(color.y, color.z)) > 1.0 || min(color.x, min(color.y, color.z)) < 0.0 {
         color.w = 0.1;
     }
-    return color;
+    color = soft_proof(oklch.proof, oklch.gamut, color);
+    return vec4<f32>(to_display(oklch.display, color.xyz), color.w);
 }
 
 fn oklch_to_rgb(okl: f32, okc: f32, okh: f32) -> vec3<f32> {
@@ -0,0 +1,66 @@
+const PROOF_NONE = 0u;
+const PROOF_CMYK = 1u;
+const PROOF_SRGB = 2u;
+const PROOF_WEB_SAFE = 3u;
+const PROOF_XTERM = 4u;
+
+// Shows `color` as reproduced on the soft proof target,
+// colors out of the target's gamut are dimmed like out of range ones
+fn soft_proof(mode: u32, gamut: array<vec4<f32>, 128>, color: vec4<f32>) -> vec4<f32> {
+    let rgb = clamp(color.xyz, vec3<f32>(0.0), vec3<f32>(1.0));
+    switch mode {
+        case PROOF_CMYK: {
+            if in_gamut(gamut, rgb) < 0.5 {
+                return vec4<f32>(color.xyz, 0.1);
+            }
+        }
+        case PROOF_SRGB: {
+            if any(rgb != color.xyz) {
+                return vec4<f32>(rgb, 0.1);
+            }
+        }
+        case PROOF_WEB_SAFE: {
+            return vec4<f32>(round(rgb * 5.0) / 5.0, color.w);
+        }
+        case PROOF_XTERM: {
+            return vec4<f32>(xterm(rgb), color.w);
+        }
+        default: {}
+    }
+    return color;
+}
+
+// Trilinear lookup in the 8x8x8 gamut mask, packed in fours
+fn in_gamut(gamut: array<vec4<f32>, 128>, rgb: vec3<f32>) -> f32 {
+    var mask = gamut;
+    let p = rgb * 7.0;
+    let i = min(floor(p), vec3<f32>(6.0));
+    let f = p - i;
+
+    var value = 0.0;
+    for (var corner = 0u; corner < 8u; corner++) {
+        let o = vec3<f32>(f32(corner & 1u), f32((corner >> 1u) & 1u), f32((corner >> 2u) & 1u));
+        let c = vec3<u32>(i + o);
+        let index = c.x + c.y * 8u + c.z * 64u;
+        let w = mix(1.0 - f, f, o);
+        value += w.x * w.y * w.z * mask[index / 4u][index % 4u];
+    }
+    return value;
+}
+
+// Nearest color of the 6x6x6 cube or the gray ramp of 256-color terminals
+fn xterm(rgb: vec3<f32>) -> vec3<f32> {
+    let v = rgb * 255.0;
+    let level = clamp(round((v - 55.0) / 40.0), vec3<f32>(1.0), vec3<f32>(5.0)) * 40.0 + 55.0;
+    let cube = select(level, vec3<f32>(0.0), v < vec3<f32>(48.0));
+    let average = (v.x + v.y + v.z) / 3.0;
+    let gray = vec3<f32>(clamp(round((average - 8.0) / 10.0), 0.0, 23.0) * 10.0 + 8.0);
+
+    let to_cube = cube - v;
+    let to_gray = gray - v;
+    if dot(to_gray, to_gray) < dot(to_cube, to_cube) {
+        return gray / 255.0;
+    }
+    return cube / 255.0;
+}
+
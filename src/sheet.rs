@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::{Display, Write};
+
+use crate::{colorspace::Oklch, fl, named};
+
+/// Swatch sizes offered, in pixels
+pub const SIZES: [u32; 4] = [64, 96, 128, 192];
+
+/// Swatches per row offered
+pub const PER_ROW: [usize; 5] = [2, 3, 4, 6, 8];
+
+/// Space around the sheet and between swatches
+const GAP: u32 = 16;
+const FONT_SIZE: u32 = 12;
+const LINE_HEIGHT: u32 = 16;
+/// Width of a character at `FONT_SIZE`, with room for the widest sans-serif fonts
+const CHAR_WIDTH: u32 = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Background {
+    #[default]
+    White,
+    Black,
+    Transparent,
+}
+
+impl Background {
+    pub const ALL: [Background; 3] = [
+        Background::White,
+        Background::Black,
+        Background::Transparent,
+    ];
+
+    fn fill(self) -> Option<&'static str> {
+        match self {
+            Background::White => Some("#ffffff"),
+            Background::Black => Some("#000000"),
+            Background::Transparent => None,
+        }
+    }
+
+    fn text(self) -> &'static str {
+        match self {
+            Background::Black => "#ffffff",
+            Background::White | Background::Transparent => "#000000",
+        }
+    }
+}
+
+impl Display for Background {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Background::White => f.write_str(&fl!("sheet-white")),
+            Background::Black => f.write_str(&fl!("sheet-black")),
+            Background::Transparent => f.write_str(&fl!("sheet-transparent")),
+        }
+    }
+}
+
+/// Text shown under each swatch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Label {
+    /// Nearest named color
+    Name,
+    Hex,
+    Rgb,
+    Oklch,
+}
+
+impl Label {
+    pub const ALL: [Label; 4] = [Label::Name, Label::Hex, Label::Rgb, Label::Oklch];
+
+    fn text(self, rgb: [f32; 3]) -> String {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let srgb = rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        match self {
+            Label::Name => named::nearest(rgb)
+                .map(|(color, _)| color.name.to_string())
+                .unwrap_or_default(),
+            Label::Hex => format!("#{}", hex::encode(srgb)),
+            Label::Rgb => format!("rgb({} {} {})", srgb[0], srgb[1], srgb[2]),
+            Label::Oklch => {
+                let [l, c, h] = Oklch::from_rgb(rgb).values;
+                format!("oklch({l:.3} {c:.3} {h:.1})")
+            }
+        }
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Label::Name => f.write_str(&fl!("sheet-name")),
+            Label::Hex => f.write_str("Hex"),
+            Label::Rgb => f.write_str("RGB"),
+            Label::Oklch => f.write_str("OKLCH"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Svg,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Svg => "svg",
+        }
+    }
+
+    pub fn mimetype(self) -> &'static str {
+        match self {
+            Format::Png => "image/png",
+            Format::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// Layout of a swatch sheet, a grid of swatches with labels
+#[derive(Clone, Debug)]
+pub struct Sheet {
+    /// Width and height of a swatch in pixels
+    pub size: u32,
+    pub per_row: usize,
+    pub background: Background,
+    /// Labels under each swatch, in order
+    pub labels: Vec<Label>,
+}
+
+impl Default for Sheet {
+    fn default() -> Self {
+        Self {
+            size: 128,
+            per_row: 4,
+            background: Background::default(),
+            labels: vec![Label::Name, Label::Hex],
+        }
+    }
+}
+
+impl Sheet {
+    /// Width and height of the sheet for `colors`
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn dimensions(&self, colors: &[[f32; 3]]) -> (u32, u32) {
+        let per_row = self.per_row.max(1);
+        let columns = colors.len().clamp(1, per_row) as u32;
+        let rows = colors.len().div_ceil(per_row).max(1) as u32;
+
+        (
+            GAP + columns * (self.cell_width(colors) + GAP),
+            GAP + rows * (self.cell_height() + GAP),
+        )
+    }
+
+    /// Width of a cell, wider than the swatch when a label of `colors` needs it
+    #[allow(clippy::cast_possible_truncation)]
+    fn cell_width(&self, colors: &[[f32; 3]]) -> u32 {
+        let longest = colors
+            .iter()
+            .flat_map(|rgb| self.labels.iter().map(|label| label.text(*rgb)))
+            .map(|text| text.chars().count() as u32)
+            .max()
+            .unwrap_or(0);
+
+        self.size.max(longest * CHAR_WIDTH)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn cell_height(&self) -> u32 {
+        self.size + self.labels.len() as u32 * LINE_HEIGHT
+    }
+
+    pub fn svg(&self, colors: &[[f32; 3]]) -> String {
+        let (width, height) = self.dimensions(colors);
+        let cell_width = self.cell_width(colors);
+        let per_row = self.per_row.max(1);
+        let text = self.background.text();
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        );
+        if let Some(fill) = self.background.fill() {
+            let _ = writeln!(
+                svg,
+                r#"<rect width="{width}" height="{height}" fill="{fill}"/>"#
+            );
+        }
+
+        for (index, rgb) in colors.iter().enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            let (column, row) = ((index % per_row) as u32, (index / per_row) as u32);
+            let x = GAP + column * (cell_width + GAP);
+            let y = GAP + row * (self.cell_height() + GAP);
+            let size = self.size;
+
+            // The outline keeps swatches close to the background visible
+            let _ = writeln!(
+                svg,
+                r##"<rect x="{x}" y="{y}" width="{size}" height="{size}" rx="4" fill="{}" stroke="#808080" stroke-opacity="0.4"/>"##,
+                Label::Hex.text(*rgb)
+            );
+            for (line, label) in (1..).zip(&self.labels) {
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{x}" y="{}" font-family="sans-serif" font-size="{FONT_SIZE}" fill="{text}">{}</text>"#,
+                    y + size + line * LINE_HEIGHT - 3,
+                    escape(&label.text(*rgb))
+                );
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the sheet with the system's fonts
+    pub fn png(&self, colors: &[[f32; 3]]) -> Option<Vec<u8>> {
+        let mut options = resvg::usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+
+        let tree = match resvg::usvg::Tree::from_str(&self.svg(colors), &options) {
+            Ok(tree) => tree,
+            Err(e) => {
+                log::error!("Failed to parse swatch sheet: {e}");
+                return None;
+            }
+        };
+
+        let size = tree.size().to_int_size();
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+
+        match pixmap.encode_png() {
+            Ok(png) => Some(png),
+            Err(e) => {
+                log::error!("Failed to encode swatch sheet: {e}");
+                None
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{escape, Background, Label, Sheet};
+
+    #[test]
+    fn dimensions() {
+        let sheet = Sheet {
+            size: 100,
+            per_row: 3,
+            background: Background::White,
+            labels: vec![Label::Hex],
+        };
+
+        // 3 columns and 2 rows of 100px swatches with a 16px label
+        assert_eq!(
+            sheet.dimensions(&[[0.5; 3]; 5]),
+            (16 + 3 * 116, 16 + 2 * 132)
+        );
+        assert_eq!(sheet.dimensions(&[[0.5; 3]]), (16 + 116, 16 + 132));
+    }
+
+    #[test]
+    fn wide_labels() {
+        let sheet = Sheet {
+            size: 64,
+            per_row: 2,
+            background: Background::White,
+            labels: vec![Label::Hex, Label::Oklch],
+        };
+        let colors = [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+
+        // Cells fit `oklch(0.452 0.313 264.1)` instead of the 64px swatch
+        let cell = 24 * 8;
+        assert_eq!(sheet.dimensions(&colors).0, 16 + 2 * (cell + 16));
+        assert!(sheet
+            .svg(&colors)
+            .contains(&format!(r#"<text x="{}""#, 16 + cell + 16)));
+    }
+
+    #[test]
+    fn svg() {
+        let sheet = Sheet::default();
+        let svg = sheet.svg(&[[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+
+        // Background and two swatches
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains(r##"fill="#ff0000""##));
+        assert!(svg.contains(">#0000ff</text>"));
+        assert!(svg.contains(">red</text>"));
+    }
+
+    #[test]
+    fn transparent() {
+        let sheet = Sheet {
+            background: Background::Transparent,
+            labels: Vec::new(),
+            ..Sheet::default()
+        };
+        let svg = sheet.svg(&[[0.5; 3]]);
+
+        assert_eq!(svg.matches("<rect").count(), 1);
+        assert!(!svg.contains("<text"));
+    }
+
+    #[test]
+    fn escaped() {
+        assert_eq!(escape("a & <b>"), "a &amp; &lt;b&gt;");
+    }
+}
@@ -0,0 +1,392 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{colorspace::rgb_to_oklab, fl, named::delta_e};
+
+static WEB_SAFE: LazyLock<Vec<Entry>> = LazyLock::new(web_safe);
+static XTERM_16: LazyLock<Vec<Entry>> = LazyLock::new(xterm_16);
+static XTERM_256: LazyLock<Vec<Entry>> = LazyLock::new(xterm_256);
+static TAILWIND_V3: LazyLock<Vec<Entry>> =
+    LazyLock::new(|| parse(include_str!("../res/palettes/tailwind-v3.txt")));
+static TAILWIND_V4: LazyLock<Vec<Entry>> =
+    LazyLock::new(|| parse(include_str!("../res/palettes/tailwind-v4.txt")));
+static MATERIAL: LazyLock<Vec<Entry>> =
+    LazyLock::new(|| parse(include_str!("../res/palettes/material.txt")));
+static OPEN_COLOR: LazyLock<Vec<Entry>> =
+    LazyLock::new(|| parse(include_str!("../res/palettes/open-color.txt")));
+
+/// Names of the 16 colors of terminals, in SGR order
+const XTERM_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Default xterm values of the 16 colors, terminal themes usually change them
+const XTERM_16_RGB: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// Fixed palettes that colors can be snapped to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    /// The 216 colors of the web-safe palette
+    WebSafe,
+    /// The 16 colors of terminals, with xterm's defaults
+    Xterm16,
+    /// The 6×6×6 cube and 24 grays of 256-color terminals,
+    /// without the 16 colors that depend on the terminal's theme
+    Xterm256,
+    TailwindV3,
+    /// sRGB clipped from the OKLCH definitions
+    TailwindV4,
+    /// The 2014 Material Design palette
+    Material,
+    OpenColor,
+    /// The palette imported in the settings
+    Imported,
+}
+
+impl Palette {
+    /// Built-in palettes, `Imported` is offered once a palette is imported
+    pub const ALL: [Palette; 7] = [
+        Palette::WebSafe,
+        Palette::Xterm16,
+        Palette::Xterm256,
+        Palette::TailwindV3,
+        Palette::TailwindV4,
+        Palette::Material,
+        Palette::OpenColor,
+    ];
+
+    /// Name shown in menus, the imported palette shows its own name
+    pub fn name(self, imported: Option<&Imported>) -> String {
+        match (self, imported) {
+            (Palette::Imported, Some(imported)) => imported.name.clone(),
+            _ => self.to_string(),
+        }
+    }
+
+    fn entries(self) -> &'static [Entry] {
+        match self {
+            Palette::WebSafe => &WEB_SAFE,
+            Palette::Xterm16 => &XTERM_16,
+            Palette::Xterm256 => &XTERM_256,
+            Palette::TailwindV3 => &TAILWIND_V3,
+            Palette::TailwindV4 => &TAILWIND_V4,
+            Palette::Material => &MATERIAL,
+            Palette::OpenColor => &OPEN_COLOR,
+            Palette::Imported => &[],
+        }
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Palette::WebSafe => f.write_str(&fl!("proof-web-safe")),
+            Palette::Xterm16 => f.write_str(&fl!("snap-xterm-16")),
+            Palette::Xterm256 => f.write_str(&fl!("proof-xterm")),
+            Palette::TailwindV3 => f.write_str("Tailwind v3"),
+            Palette::TailwindV4 => f.write_str("Tailwind v4"),
+            Palette::Material => f.write_str("Material Design"),
+            Palette::OpenColor => f.write_str("Open Color"),
+            Palette::Imported => f.write_str(&fl!("snap-imported")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub name: String,
+    /// SGR parameters that select the color as a terminal foreground, e.g. `38;5;196`
+    pub ansi: Option<String>,
+    pub rgb: [f32; 3],
+    lab: [f32; 3],
+}
+
+impl Entry {
+    fn new(name: String, ansi: Option<String>, srgb: [u8; 3]) -> Self {
+        let rgb = srgb.map(|c| f32::from(c) / 255.0);
+        Self {
+            name,
+            ansi,
+            rgb,
+            lab: rgb_to_oklab(rgb[0], rgb[1], rgb[2]),
+        }
+    }
+}
+
+/// A palette loaded from a GIMP `.gpl` file or a list of `#rrggbb name` lines
+pub struct Imported {
+    pub name: String,
+    entries: Vec<Entry>,
+}
+
+impl Imported {
+    pub fn load(path: &Path) -> Option<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("Failed to read palette {path:?}: {e}");
+                return None;
+            }
+        };
+
+        let imported = parse_imported(&text, path);
+        if imported.entries.is_empty() {
+            log::error!("{path:?} has no colors");
+            return None;
+        }
+
+        Some(imported)
+    }
+}
+
+/// Built-in palettes, followed by `imported` once loaded
+pub fn available(imported: Option<&Imported>) -> Vec<Palette> {
+    let mut palettes = Palette::ALL.to_vec();
+    if imported.is_some() {
+        palettes.push(Palette::Imported);
+    }
+
+    palettes
+}
+
+/// Finds the entry of `palette` closest to `rgb`, `imported` is searched for
+/// `Palette::Imported`. Returns it with its ΔE (euclidean distance in OKLAB)
+pub fn nearest(
+    palette: Palette,
+    rgb: [f32; 3],
+    imported: Option<&Imported>,
+) -> Option<(Entry, f32)> {
+    let lab = rgb_to_oklab(rgb[0], rgb[1], rgb[2]);
+    let find = |entries: &[Entry]| {
+        entries
+            .iter()
+            .map(|entry| (entry, delta_e(&entry.lab, &lab)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entry, delta)| (entry.clone(), delta))
+    };
+
+    if palette == Palette::Imported {
+        return find(&imported?.entries);
+    }
+    find(palette.entries())
+}
+
+/// Parses a built-in table, each line is `#rrggbb name`
+fn parse(table: &str) -> Vec<Entry> {
+    table
+        .lines()
+        .filter_map(|line| {
+            let (srgb, name) = parse_hex(line)?;
+            Some(Entry::new(name.to_string(), None, srgb))
+        })
+        .collect()
+}
+
+/// Parses `#rrggbb name`, the name is optional
+fn parse_hex(line: &str) -> Option<([u8; 3], &str)> {
+    let line = line.trim().strip_prefix('#').unwrap_or(line.trim());
+    let (hex, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let srgb = hex::decode(hex).ok()?;
+    let [r, g, b] = srgb[..] else {
+        return None;
+    };
+
+    Some(([r, g, b], name.trim()))
+}
+
+/// Parses `r g b name` lines of GIMP palettes, the name is optional
+fn parse_gpl(line: &str) -> Option<([u8; 3], &str)> {
+    let mut rest = line.trim();
+    let mut srgb = [0; 3];
+    for channel in &mut srgb {
+        let (value, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        *channel = value.parse().ok()?;
+        rest = tail.trim_start();
+    }
+
+    Some((srgb, rest.trim()))
+}
+
+fn parse_imported(text: &str, path: &Path) -> Imported {
+    let mut name = None;
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        if let Some(palette_name) = line.strip_prefix("Name:") {
+            name = Some(palette_name.trim().to_string());
+            continue;
+        }
+
+        let Some((srgb, color_name)) = parse_gpl(line).or_else(|| parse_hex(line)) else {
+            continue;
+        };
+        // GIMP names unnamed colors "Untitled"
+        let color_name = if color_name.is_empty() || color_name == "Untitled" {
+            format!("#{}", hex::encode(srgb))
+        } else {
+            color_name.to_string()
+        };
+        entries.push(Entry::new(color_name, None, srgb));
+    }
+
+    Imported {
+        name: name
+            .filter(|name| !name.is_empty())
+            .or_else(|| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_default(),
+        entries,
+    }
+}
+
+fn web_safe() -> Vec<Entry> {
+    let mut entries = Vec::with_capacity(216);
+    for r in 0..6 {
+        for g in 0..6 {
+            for b in 0..6 {
+                let srgb = [r * 51, g * 51, b * 51];
+                entries.push(Entry::new(format!("#{}", hex::encode(srgb)), None, srgb));
+            }
+        }
+    }
+
+    entries
+}
+
+fn xterm_16() -> Vec<Entry> {
+    XTERM_16_RGB
+        .iter()
+        .enumerate()
+        .map(|(index, srgb)| {
+            let (name, sgr) = if index < 8 {
+                (XTERM_NAMES[index].to_string(), 30 + index)
+            } else {
+                (format!("bright {}", XTERM_NAMES[index - 8]), 90 + index - 8)
+            };
+            Entry::new(name, Some(sgr.to_string()), *srgb)
+        })
+        .collect()
+}
+
+fn xterm_256() -> Vec<Entry> {
+    let level = |i: u8| if i == 0 { 0 } else { i * 40 + 55 };
+
+    let cube = (0..216u8).map(|i| [level(i / 36), level(i / 6 % 6), level(i % 6)]);
+    let grays = (0..24u8).map(|i| [i * 10 + 8; 3]);
+    cube.chain(grays)
+        .zip(16..=255u8)
+        .map(|(srgb, index)| Entry::new(index.to_string(), Some(format!("38;5;{index}")), srgb))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{available, nearest, parse_imported, Palette};
+
+    #[test]
+    fn tables_parse() {
+        let count = |palette: Palette| palette.entries().len();
+
+        assert_eq!(count(Palette::WebSafe), 216);
+        assert_eq!(count(Palette::Xterm16), 16);
+        assert_eq!(count(Palette::Xterm256), 240);
+        assert_eq!(count(Palette::TailwindV3), 244);
+        assert_eq!(count(Palette::TailwindV4), 244);
+        assert_eq!(count(Palette::Material), 256);
+        assert_eq!(count(Palette::OpenColor), 132);
+    }
+
+    #[test]
+    fn nearest_exact() {
+        let rgb = |r: u8, g: u8, b: u8| [r, g, b].map(|c| f32::from(c) / 255.0);
+
+        let (entry, delta) = nearest(Palette::TailwindV3, rgb(239, 68, 68), None).unwrap();
+        assert_eq!(entry.name, "red-500");
+        assert!(delta < 1e-4);
+
+        let (entry, _) = nearest(Palette::Material, rgb(63, 81, 181), None).unwrap();
+        assert_eq!(entry.name, "indigo 500");
+
+        let (entry, _) = nearest(Palette::Xterm256, rgb(255, 0, 0), None).unwrap();
+        assert_eq!(entry.name, "196");
+        assert_eq!(entry.ansi.as_deref(), Some("38;5;196"));
+
+        let (entry, _) = nearest(Palette::Xterm256, rgb(128, 128, 128), None).unwrap();
+        assert_eq!(entry.ansi.as_deref(), Some("38;5;244"));
+    }
+
+    #[test]
+    fn nearest_close() {
+        let (entry, delta) = nearest(Palette::Xterm16, [0.75, 0.05, 0.0], None).unwrap();
+
+        assert_eq!(entry.name, "red");
+        assert_eq!(entry.ansi.as_deref(), Some("31"));
+        assert!(delta > 0.0);
+
+        let (entry, _) = nearest(Palette::WebSafe, [0.21, 0.39, 0.62], None).unwrap();
+        assert_eq!(entry.name, "#336699");
+    }
+
+    #[test]
+    fn import_gpl() {
+        let text = "GIMP Palette\nName: Brand\nColumns: 2\n# comment\n\
+                    255   0   0 Primary\n  0 128 255\tSky blue\n 18 52 86 Untitled\n";
+        let imported = parse_imported(text, Path::new("brand.gpl"));
+
+        assert_eq!(imported.name, "Brand");
+        let names: Vec<_> = imported.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Primary", "Sky blue", "#123456"]);
+        assert_eq!(imported.entries[1].rgb, [0.0, 128.0 / 255.0, 1.0]);
+    }
+
+    #[test]
+    fn import_hex() {
+        let text = "#ff8800 accent\n112233\n\nnot a color\n";
+        let imported = parse_imported(text, Path::new("/tmp/brand colors.txt"));
+
+        assert_eq!(imported.name, "brand colors");
+        let names: Vec<_> = imported.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["accent", "#112233"]);
+    }
+
+    #[test]
+    fn nearest_imported() {
+        let imported = parse_imported(
+            "#ff0000 Primary
+#0080ff Sky
+",
+            Path::new("brand.txt"),
+        );
+
+        let (entry, delta) = nearest(Palette::Imported, [1.0, 0.0, 0.0], Some(&imported)).unwrap();
+        assert_eq!(entry.name, "Primary");
+        assert!(delta < 1e-4);
+        assert!(nearest(Palette::Imported, [1.0, 0.0, 0.0], None).is_none());
+
+        assert_eq!(available(Some(&imported)).last(), Some(&Palette::Imported));
+        assert!(!available(None).contains(&Palette::Imported));
+        assert_eq!(Palette::Imported.name(Some(&imported)), "brand");
+    }
+}
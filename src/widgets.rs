@@ -3,8 +3,12 @@ use std::{f32::consts::FRAC_PI_2, ops::RangeInclusive};
 use cosmic::{
     iced::{
         border,
-        core::{layout, renderer, widget::tree},
+        core::{
+            layout, renderer,
+            widget::{operation, tree, Operation},
+        },
         gradient::{ColorStop, Linear},
+        keyboard::{self, key::Named},
         mouse, touch, Background, Border, Color, Element, Event, Gradient, Length, Padding, Point,
         Rectangle, Shadow, Size,
     },
@@ -12,6 +16,8 @@ use cosmic::{
     widget::{self, Widget},
 };
 
+use crate::display::{self, DisplayProfile};
+
 pub struct ColorBlock {
     color: Color,
     border: [bool; 4],
@@ -20,10 +26,17 @@ pub struct ColorBlock {
     tooltip: Option<String>,
 }
 
+/// `color` as sent to a monitor with `profile`, `None` leaves it unmanaged
+fn to_display(profile: Option<&DisplayProfile>, color: Color) -> Color {
+    let [r, g, b] = display::to_display(profile, [color.r, color.g, color.b]);
+    Color { r, g, b, ..color }
+}
+
 impl ColorBlock {
-    pub fn new(color: Color) -> Self {
+    /// Shows `color` through the display profile, `None` shows it unmanaged
+    pub fn new(color: Color, display: Option<&DisplayProfile>) -> Self {
         Self {
-            color,
+            color: to_display(display, color),
             border: [true; 4],
             width: Length::Fill,
             height: Length::Fill,
@@ -101,24 +114,54 @@ impl<'a, Message: 'a> From<ColorBlock> for cosmic::Element<'a, Message> {
     }
 }
 
-pub fn color_block(color: Color) -> ColorBlock {
-    ColorBlock::new(color)
+pub fn color_block(color: Color, display: Option<&DisplayProfile>) -> ColorBlock {
+    ColorBlock::new(color, display)
 }
 
+/// Text field of a color space channel, showing `error` under it when set
+pub fn channel_input<'a, Message>(
+    value: &str,
+    error: Option<String>,
+    on_input: impl Fn(String) -> Message + 'a,
+    on_submit: Message,
+) -> cosmic::Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let input = widget::text_input("", value.to_string())
+        .on_input(on_input)
+        .on_submit(move |_| on_submit.clone());
+
+    match error {
+        Some(error) => input.error(error).into(),
+        None => input.into(),
+    }
+}
+
+/// Slider over `range` moving `scroll_step` of the range per scroll step,
+/// with its gradient shown through the display profile like the swatches
 pub fn color_slider<'a, Message>(
     range: RangeInclusive<f32>,
     value: f32,
     on_change: impl Fn(f32) -> Message + 'a,
-    color_stops: &'static [ColorStop],
+    color_stops: &[ColorStop],
+    scroll_step: f32,
+    display: Option<&DisplayProfile>,
 ) -> cosmic::Element<'a, Message>
 where
     Message: Clone + 'a,
 {
+    let color_stops = color_stops.iter().map(|stop| ColorStop {
+        color: to_display(display, stop.color),
+        ..*stop
+    });
+
     ColorSlider {
         value,
         range,
-        background: Gradient::Linear(Linear::new(FRAC_PI_2).add_stops(color_stops.iter().copied())),
-        scroll_steps: 0.01,
+        background: Gradient::Linear(Linear::new(FRAC_PI_2).add_stops(color_stops)),
+        scroll_steps: scroll_step,
+        key_steps: 0.01,
         on_change: Box::new(on_change),
     }
     .into()
@@ -128,16 +171,19 @@ pub struct ColorSlider<'a, Message> {
     value: f32,
     range: RangeInclusive<f32>,
     background: Gradient,
+    /// Fraction of the range moved per scroll step
     scroll_steps: f32,
+    /// Fraction of the range moved by an arrow key
+    key_steps: f32,
     on_change: Box<dyn Fn(f32) -> Message + 'a>,
 }
 
-impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for ColorSlider<'_, Message>
+impl<Message, Renderer> Widget<Message, cosmic::Theme, Renderer> for ColorSlider<'_, Message>
 where
     Renderer: renderer::Renderer,
 {
     fn state(&self) -> cosmic::iced::core::widget::tree::State {
-        tree::State::new(ColorSliderState { is_dragging: false })
+        tree::State::new(ColorSliderState::default())
     }
 
     fn size(&self) -> cosmic::iced::Size<Length> {
@@ -156,11 +202,22 @@ where
         layout::Node::new(Size::new(limits.max().width, 15.0))
     }
 
+    fn operate(
+        &mut self,
+        tree: &mut cosmic::iced::core::widget::Tree,
+        layout: layout::Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let state = tree.state.downcast_mut::<ColorSliderState>();
+        operation.focusable(None, layout.bounds(), state);
+    }
+
     fn draw(
         &self,
-        _tree: &cosmic::iced::core::widget::Tree,
+        tree: &cosmic::iced::core::widget::Tree,
         renderer: &mut Renderer,
-        _theme: &Theme,
+        theme: &cosmic::Theme,
         _style: &renderer::Style,
         layout: cosmic::iced::core::Layout<'_>,
         _cursor: cosmic::iced::core::mouse::Cursor,
@@ -177,10 +234,19 @@ where
             height: bounds.height,
         };
 
+        let is_focused = tree.state.downcast_ref::<ColorSliderState>().is_focused;
         renderer.fill_quad(
             renderer::Quad {
                 bounds: rail_bounds,
-                border: border::rounded(10),
+                border: if is_focused {
+                    Border {
+                        color: theme.cosmic().accent_color().into(),
+                        width: 1.0,
+                        radius: 10f32.into(),
+                    }
+                } else {
+                    border::rounded(10)
+                },
                 shadow: Shadow {
                     ..Default::default()
                 },
@@ -231,8 +297,11 @@ where
             | Event::Touch(touch::Event::FingerPressed { .. }) => {
                 if let Some(position) = cursor.position_over(layout.bounds()) {
                     state.is_dragging = true;
+                    state.is_focused = true;
                     change(&position);
                     shell.capture_event();
+                } else {
+                    state.is_focused = false;
                 }
             }
             Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
@@ -258,7 +327,8 @@ where
                     match delta {
                         mouse::ScrollDelta::Lines { y, .. }
                         | mouse::ScrollDelta::Pixels { y, .. } => {
-                            self.value += *y * self.scroll_steps;
+                            let range = self.range.end() - self.range.start();
+                            self.value += *y * self.scroll_steps * range;
                             self.value = self.value.clamp(*self.range.start(), *self.range.end());
                             shell.publish((self.on_change)(self.value));
                         }
@@ -266,6 +336,39 @@ where
                     shell.capture_event();
                 }
             }
+
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                if state.is_focused =>
+            {
+                let (start, end) = (*self.range.start(), *self.range.end());
+                // Shift for fine steps, Ctrl for coarse steps
+                let step = (end - start)
+                    * self.key_steps
+                    * if modifiers.shift() {
+                        0.1
+                    } else if modifiers.control() {
+                        10.0
+                    } else {
+                        1.0
+                    };
+
+                let value = match key.as_ref() {
+                    keyboard::Key::Named(Named::ArrowLeft | Named::ArrowDown) => self.value - step,
+                    keyboard::Key::Named(Named::ArrowRight | Named::ArrowUp) => self.value + step,
+                    keyboard::Key::Named(Named::Home) => start,
+                    keyboard::Key::Named(Named::End) => end,
+                    keyboard::Key::Named(Named::Escape) => {
+                        state.is_focused = false;
+                        shell.capture_event();
+                        return;
+                    }
+                    _ => return,
+                };
+
+                self.value = value.clamp(start, end);
+                shell.publish((self.on_change)(self.value));
+                shell.capture_event();
+            }
             _ => (),
         }
     }
@@ -290,8 +393,8 @@ where
     }
 }
 
-impl<'a, Message, Theme, Renderer> From<ColorSlider<'a, Message>>
-    for Element<'a, Message, Theme, Renderer>
+impl<'a, Message, Renderer> From<ColorSlider<'a, Message>>
+    for Element<'a, Message, cosmic::Theme, Renderer>
 where
     Renderer: renderer::Renderer,
     Message: Clone + 'a,
@@ -301,7 +404,22 @@ where
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct ColorSliderState {
     is_dragging: bool,
+    is_focused: bool,
+}
+
+impl operation::Focusable for ColorSliderState {
+    fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.is_focused = false;
+    }
 }